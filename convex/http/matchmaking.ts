@@ -602,6 +602,7 @@ export const wagerJoin = authX402HttpAction(
       // and chains to collectHostDeposit automatically.
       await ctx.scheduler.runAfter(0, internalAny.wager.escrow.initializeEscrow, {
         lobbyId,
+        opponentPaymentSignature: payment.signature,
       });
 
       // Also schedule confirmOpponentDeposit as a fallback — if the PDA was
@@ -610,6 +611,7 @@ export const wagerJoin = authX402HttpAction(
       await ctx.scheduler.runAfter(0, internalAny.wager.escrow.confirmOpponentDeposit, {
         lobbyId,
         depositorWallet: payment.payer,
+        paymentSignature: payment.signature,
       });
 
       // Join the lobby (starts game if both deposits confirmed)
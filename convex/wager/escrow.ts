@@ -77,6 +77,31 @@ const MATCH_ESCROW_PROGRAM_ID = new PublicKey(
 /** PDA seed prefix for escrow accounts */
 const ESCROW_SEED_PREFIX = Buffer.from("escrow");
 
+/** Singleton ProgramConfig PDA (seeds: [b"config"]) holding the pause switch */
+const CONFIG_PDA = PublicKey.findProgramAddressSync(
+  [Buffer.from("config")],
+  MATCH_ESCROW_PROGRAM_ID
+)[0];
+
+/** Seconds both players have to fund a new escrow before it can be reclaimed */
+const DEPOSIT_WINDOW_SECS = 30 * 60;
+
+/** Seconds after the deposit deadline for the match to finish */
+const MATCH_WINDOW_SECS = 2 * 60 * 60;
+
+/** Seconds after the match deadline for the result to be posted */
+const SETTLEMENT_WINDOW_SECS = 24 * 60 * 60;
+
+/** On-chain `ForfeitReason` codes, recorded in the EscrowForfeited event */
+const FORFEIT_REASON_CODES = {
+  disconnect: 0,
+  cheating: 1,
+  surrender: 2,
+  timeout: 3,
+} as const;
+
+type ForfeitReason = keyof typeof FORFEIT_REASON_CODES;
+
 /** Maximum retries for settlement before giving up (cron will retry later) */
 const MAX_SETTLE_RETRIES = 3;
 
@@ -150,6 +175,16 @@ async function deriveEscrowPda(lobbyId: string) {
   return { pda, bump, lobbyIdHash };
 }
 
+/**
+ * Derive the on-chain payment_ref for an x402 payment: sha256 of its
+ * settlement signature. confirm_deposit stores it per seat, so a retried
+ * confirm of the same payment is a no-op rather than a second credit.
+ */
+async function paymentRefFromSignature(signature: string): Promise<Buffer> {
+  const data = new TextEncoder().encode(signature);
+  return Buffer.from(await crypto.subtle.digest("SHA-256", data));
+}
+
 // ============================================================================
 // HELPERS
 // ============================================================================
@@ -293,47 +328,99 @@ async function recordTransaction(
 // ANCHOR INSTRUCTION BUILDERS
 // ============================================================================
 
+/** Borsh `Option<T>`: a 0 tag for None, or a 1 tag followed by the value */
+function borshOption(value?: Buffer): Buffer {
+  return value ? Buffer.concat([Buffer.from([1]), value]) : Buffer.from([0]);
+}
+
+function borshU64(value: bigint): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(value);
+  return buf;
+}
+
+function borshI64(value: bigint): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigInt64LE(value);
+  return buf;
+}
+
 /**
  * Build the initialize_escrow instruction.
  *
- * Accounts: authority (signer, mut), escrow (PDA, init), system_program
- * Args: lobby_id_hash [u8;32], host Pubkey, opponent Pubkey,
- *       wager_lamports u64, token_mint Pubkey, treasury Pubkey
+ * Accounts: authority (signer), payer (signer, mut), escrow (PDA, init),
+ *   config (PDA), system_program
+ * Args: InitializeEscrowParams. Only the fields set below vary per lobby;
+ *   the rest take the program defaults (one round, default fee, no
+ *   referral, burn, oracle or slot deadlines).
  */
 async function buildInitializeEscrowIx(params: {
   escrowPda: PublicKey;
   authority: PublicKey;
+  /** Funds the escrow's rent and gets it back when the escrow closes */
+  payer: PublicKey;
   lobbyIdHash: Buffer;
   host: PublicKey;
   opponent: PublicKey;
   wagerLamports: bigint;
   tokenMint: PublicKey;
   treasury: PublicKey;
+  depositDeadline: bigint;
+  matchDeadline: bigint;
+  settlementDeadline: bigint;
 }): Promise<TransactionInstruction> {
   const disc = await anchorDiscriminator("initialize_escrow");
-
-  // Data: disc(8) + lobby_id_hash(32) + host(32) + opponent(32)
-  //       + wager_lamports(8) + token_mint(32) + treasury(32) = 176 bytes
-  const data = Buffer.alloc(176);
-  let offset = 0;
-  disc.copy(data, offset);
-  offset += 8;
-  params.lobbyIdHash.copy(data, offset);
-  offset += 32;
-  params.host.toBuffer().copy(data, offset);
-  offset += 32;
-  params.opponent.toBuffer().copy(data, offset);
-  offset += 32;
-  data.writeBigUInt64LE(params.wagerLamports, offset);
-  offset += 8;
-  params.tokenMint.toBuffer().copy(data, offset);
-  offset += 32;
-  params.treasury.toBuffer().copy(data, offset);
+  const none = borshOption();
+  const u8 = (value: number) => Buffer.from([value]);
+  const u16 = (value: number) => Buffer.from([value & 0xff, value >> 8]);
+
+  // Fields in InitializeEscrowParams declaration order
+  const data = Buffer.concat([
+    disc,
+    params.lobbyIdHash,
+    params.host.toBuffer(),
+    params.opponent.toBuffer(),
+    borshU64(params.wagerLamports), // host_wager
+    borshU64(params.wagerLamports), // opponent_wager
+    params.tokenMint.toBuffer(),
+    params.treasury.toBuffer(),
+    borshI64(params.depositDeadline),
+    none, // fee_bps
+    borshI64(params.matchDeadline),
+    u8(1), // rounds_to_win
+    u8(0), // free_entry
+    none, // min_wager
+    none, // max_wager
+    u8(0), // retain_record
+    none, // deposit_deadline_slot
+    none, // match_deadline_slot
+    u8(0), // use_global_treasury
+    u8(0), // require_authorized_deposit
+    none, // match_ref
+    borshI64(params.settlementDeadline),
+    none, // oracle
+    none, // referrer
+    u16(0), // referral_bps
+    u8(0), // rounding: floor
+    none, // required_collection
+    none, // settle_authority: the authority itself
+    u8(0), // allow_player_treasury
+    u16(0), // burn_bps
+    borshU64(0n), // min_fee
+    borshU64(0n), // max_fee
+    borshI64(0n), // min_match_duration
+    none, // default_winner
+    u16(0), // crank_reward_bps
+    u8(0), // require_join_approval
+    u8(0), // sponsored
+  ]);
 
   return new TransactionInstruction({
     keys: [
-      { pubkey: params.authority, isSigner: true, isWritable: true },
+      { pubkey: params.authority, isSigner: true, isWritable: false },
+      { pubkey: params.payer, isSigner: true, isWritable: true },
       { pubkey: params.escrowPda, isSigner: false, isWritable: true },
+      { pubkey: CONFIG_PDA, isSigner: false, isWritable: false },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: MATCH_ESCROW_PROGRAM_ID,
@@ -344,9 +431,12 @@ async function buildInitializeEscrowIx(params: {
 /**
  * Build the deposit instruction.
  *
- * Accounts: depositor (signer, mut), escrow (PDA, mut),
+ * Accounts: depositor (signer, mut), escrow (PDA, mut), config (PDA),
+ *   authority (optional signer), delegate (optional signer),
  *   depositor_token_account (optional), escrow_token_account (optional),
- *   token_program (optional), system_program
+ *   mint (optional), token_program (optional),
+ *   entry_nft_token_account (optional), entry_nft_metadata (optional),
+ *   player_index (optional), system_program
  * Args: none (wager amount read from escrow state)
  */
 async function buildDepositIx(params: {
@@ -367,15 +457,23 @@ async function buildDepositIx(params: {
   const escrowTokenAccount = nativeSol
     ? absent
     : getAssociatedTokenAddressSync(params.escrowPda, mintPubkey);
+  const mint = nativeSol ? absent : mintPubkey;
   const tokenProgram = nativeSol ? absent : TOKEN_PROGRAM_ID;
 
   return new TransactionInstruction({
     keys: [
       { pubkey: params.depositor, isSigner: true, isWritable: true },
       { pubkey: params.escrowPda, isSigner: false, isWritable: true },
+      { pubkey: CONFIG_PDA, isSigner: false, isWritable: false },
+      { pubkey: absent, isSigner: false, isWritable: false }, // authority
+      { pubkey: absent, isSigner: false, isWritable: false }, // delegate
       { pubkey: depositorTokenAccount, isSigner: false, isWritable: !nativeSol },
       { pubkey: escrowTokenAccount, isSigner: false, isWritable: !nativeSol },
+      { pubkey: mint, isSigner: false, isWritable: false },
       { pubkey: tokenProgram, isSigner: false, isWritable: false },
+      { pubkey: absent, isSigner: false, isWritable: false }, // entry_nft_token_account
+      { pubkey: absent, isSigner: false, isWritable: false }, // entry_nft_metadata
+      { pubkey: absent, isSigner: false, isWritable: false }, // player_index
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: MATCH_ESCROW_PROGRAM_ID,
@@ -386,31 +484,47 @@ async function buildDepositIx(params: {
 /**
  * Build the settle or forfeit instruction.
  *
- * Accounts: authority (signer, mut), escrow (PDA, mut, close),
- *   winner (mut), treasury (mut),
+ * Accounts: settle_authority (signer), escrow (PDA, mut, close),
+ *   rent_payer (mut), winner (mut), treasury (mut),
  *   winner_token_account (optional), treasury_token_account (optional),
- *   escrow_token_account (optional), token_program (optional), system_program
+ *   escrow_token_account (optional), mint (optional, mut),
+ *   token_program (optional), config (optional), referrer (optional),
+ *   referrer_token_account (optional), result_log (optional),
+ *   fee_vault (optional), host_index (optional), opponent_index (optional),
+ *   payout_recipient (optional), system_program
  *
- * Settle args: winner Pubkey
- * Forfeit args: forfeiter Pubkey
+ * Settle args: winner Pubkey, rent_to_winner bool
+ * Forfeit args: forfeiter Pubkey, reason u8, rent_to_winner bool
  */
 async function buildSettleOrForfeitIx(params: {
   reason: "settle" | "forfeit";
   escrowPda: PublicKey;
   authority: PublicKey;
+  /** The escrow's recorded rent payer, credited when it closes */
+  rentPayer: PublicKey;
   winnerWallet: PublicKey;
   loserWallet: PublicKey;
   treasuryWallet: PublicKey;
   mint: string;
+  forfeitReason: ForfeitReason;
+  /** Send the escrow's reclaimed rent to the winner instead of rent_payer */
+  rentToWinner: boolean;
 }): Promise<TransactionInstruction> {
   const isForfeit = params.reason === "forfeit";
   const disc = await anchorDiscriminator(params.reason);
 
-  // Settle: arg = winner pubkey. Forfeit: arg = forfeiter pubkey.
+  // Settle: winner pubkey. Forfeit: forfeiter pubkey + reason. Both end
+  // with rent_to_winner.
   const argPubkey = isForfeit ? params.loserWallet : params.winnerWallet;
-  const data = Buffer.alloc(40);
+  const data = Buffer.alloc(isForfeit ? 42 : 41);
   disc.copy(data, 0);
   argPubkey.toBuffer().copy(data, 8);
+  let offset = 40;
+  if (isForfeit) {
+    data.writeUInt8(FORFEIT_REASON_CODES[params.forfeitReason], offset);
+    offset += 1;
+  }
+  data.writeUInt8(params.rentToWinner ? 1 : 0, offset);
 
   const nativeSol = isNativeSolMint(params.mint);
   const mintPubkey = new PublicKey(params.mint);
@@ -425,18 +539,24 @@ async function buildSettleOrForfeitIx(params: {
   const escrowTokenAccount = nativeSol
     ? absent
     : getAssociatedTokenAddressSync(params.escrowPda, mintPubkey);
+  const mint = nativeSol ? absent : mintPubkey;
   const tokenProgram = nativeSol ? absent : TOKEN_PROGRAM_ID;
 
   return new TransactionInstruction({
     keys: [
       { pubkey: params.authority, isSigner: true, isWritable: true },
       { pubkey: params.escrowPda, isSigner: false, isWritable: true },
+      { pubkey: params.rentPayer, isSigner: false, isWritable: true },
       { pubkey: params.winnerWallet, isSigner: false, isWritable: true },
       { pubkey: params.treasuryWallet, isSigner: false, isWritable: true },
       { pubkey: winnerTokenAccount, isSigner: false, isWritable: !nativeSol },
       { pubkey: treasuryTokenAccount, isSigner: false, isWritable: !nativeSol },
       { pubkey: escrowTokenAccount, isSigner: false, isWritable: !nativeSol },
+      { pubkey: mint, isSigner: false, isWritable: !nativeSol },
       { pubkey: tokenProgram, isSigner: false, isWritable: false },
+      // config, referrer, referrer_token_account, result_log, fee_vault,
+      // host_index, opponent_index, payout_recipient: unused for lobbies
+      ...Array(8).fill({ pubkey: absent, isSigner: false, isWritable: false }),
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: MATCH_ESCROW_PROGRAM_ID,
@@ -448,17 +568,26 @@ async function buildSettleOrForfeitIx(params: {
  * Build the confirm_deposit instruction (authority-only).
  *
  * Accounts: authority (signer), escrow (PDA, mut)
- * Args: depositor Pubkey
+ * Args: depositor Pubkey, payment_ref [u8;32], confirm_nonce u64
+ *
+ * The nonce must exceed the escrow's last confirmed one; a millisecond
+ * timestamp keeps it increasing across server calls.
  */
 async function buildConfirmDepositIx(params: {
   authority: PublicKey;
   escrowPda: PublicKey;
   depositor: PublicKey;
+  paymentSignature: string;
 }): Promise<TransactionInstruction> {
   const disc = await anchorDiscriminator("confirm_deposit");
-  const data = Buffer.alloc(40);
+  const paymentRef = await paymentRefFromSignature(params.paymentSignature);
+
+  // Data: disc(8) + depositor(32) + payment_ref(32) + confirm_nonce(8) = 80 bytes
+  const data = Buffer.alloc(80);
   disc.copy(data, 0);
   params.depositor.toBuffer().copy(data, 8);
+  paymentRef.copy(data, 40);
+  data.writeBigUInt64LE(BigInt(Date.now()), 72);
 
   return new TransactionInstruction({
     keys: [
@@ -484,10 +613,13 @@ async function buildConfirmDepositIx(params: {
  * After initialization, schedules collectHostDeposit for agent hosts.
  *
  * @param lobbyId - The game lobby to initialize escrow for
+ * @param opponentPaymentSignature - The opponent's x402 payment signature,
+ *   required when their deposit is confirmed here
  */
 export const initializeEscrow = internalAction({
   args: {
     lobbyId: v.id("gameLobbies"),
+    opponentPaymentSignature: v.optional(v.string()),
   },
   handler: async (ctx, args) => {
     // 1. Query lobby to get crypto wager fields
@@ -553,15 +685,21 @@ export const initializeEscrow = internalAction({
     transaction.feePayer = authorityPubkey;
 
     // 5a. initialize_escrow instruction
+    const depositDeadline = BigInt(Math.floor(Date.now() / 1000) + DEPOSIT_WINDOW_SECS);
+    const matchDeadline = depositDeadline + BigInt(MATCH_WINDOW_SECS);
     const initIx = await buildInitializeEscrowIx({
       escrowPda: pda,
       authority: authorityPubkey,
+      payer: authorityPubkey,
       lobbyIdHash,
       host: new PublicKey(hostWallet),
       opponent: new PublicKey(opponentWallet),
       wagerLamports: wagerAtomicUnits,
       tokenMint: new PublicKey(mint),
       treasury: authorityPubkey,
+      depositDeadline,
+      matchDeadline,
+      settlementDeadline: matchDeadline + BigInt(SETTLEMENT_WINDOW_SECS),
     });
     transaction.add(initIx);
 
@@ -569,6 +707,11 @@ export const initializeEscrow = internalAction({
     //     treasury to PDA (x402 paid to treasury, not PDA) and confirm the
     //     deposit flag onchain — all in the same atomic transaction.
     if (lobby.cryptoOpponentDeposited) {
+      if (!args.opponentPaymentSignature) {
+        throw new Error(
+          `Opponent deposited for lobby ${args.lobbyId} but no x402 payment signature was passed`
+        );
+      }
       const mintStr = getMintForCurrency(currency);
       if (isNativeSolMint(mintStr)) {
         // SOL: system transfer from treasury to escrow PDA
@@ -623,6 +766,7 @@ export const initializeEscrow = internalAction({
         authority: authorityPubkey,
         escrowPda: pda,
         depositor: new PublicKey(opponentWallet),
+        paymentSignature: args.opponentPaymentSignature,
       });
       transaction.add(confirmIx);
     }
@@ -796,11 +940,13 @@ export const collectHostDeposit = internalAction({
  *
  * @param lobbyId - The game lobby
  * @param depositorWallet - The wallet address to confirm
+ * @param paymentSignature - The x402 payment signature, hashed into payment_ref
  */
 export const confirmOpponentDeposit = internalAction({
   args: {
     lobbyId: v.id("gameLobbies"),
     depositorWallet: v.string(),
+    paymentSignature: v.string(),
   },
   handler: async (ctx, args) => {
     const lobby = await ctx.runQuery(internalAny.gameplay.games.queries.getLobbyInternal, {
@@ -831,6 +977,7 @@ export const confirmOpponentDeposit = internalAction({
       authority: authorityPubkey,
       escrowPda: escrowPdaPubkey,
       depositor: depositorPubkey,
+      paymentSignature: args.paymentSignature,
     });
     transaction.add(confirmIx);
 
@@ -878,12 +1025,21 @@ export const settleEscrow = internalAction({
  * @param lobbyId - The game lobby to forfeit-settle
  * @param winnerId - The non-forfeiting player's user ID
  * @param loserId - The forfeiting/disconnected player's user ID
+ * @param forfeitReason - Why the loser forfeited; defaults to "disconnect"
  */
 export const forfeitEscrow = internalAction({
   args: {
     lobbyId: v.id("gameLobbies"),
     winnerId: v.id("users"),
     loserId: v.id("users"),
+    forfeitReason: v.optional(
+      v.union(
+        v.literal("disconnect"),
+        v.literal("cheating"),
+        v.literal("surrender"),
+        v.literal("timeout")
+      )
+    ),
   },
   handler: async (ctx, args) => {
     return await _settleOrForfeit(ctx, args, "forfeit");
@@ -903,7 +1059,7 @@ export const forfeitEscrow = internalAction({
  *   - forfeit(forfeiter) — for DC/surrender (determines winner onchain)
  *
  * @param ctx - Convex action context
- * @param args - Lobby ID, winner ID, loser ID
+ * @param args - Lobby ID, winner ID, loser ID, and the forfeit reason if any
  * @param reason - "settle" for normal game end, "forfeit" for DC/forfeit
  */
 async function _settleOrForfeit(
@@ -912,6 +1068,7 @@ async function _settleOrForfeit(
     lobbyId: string;
     winnerId: string;
     loserId: string;
+    forfeitReason?: ForfeitReason;
   },
   reason: "settle" | "forfeit"
 ) {
@@ -984,10 +1141,14 @@ async function _settleOrForfeit(
     reason,
     escrowPda: escrowPdaPubkey,
     authority: authorityPubkey,
+    // initializeEscrow has the treasury wallet pay the escrow's rent
+    rentPayer: authorityPubkey,
     winnerWallet: new PublicKey(winnerWallet),
     loserWallet: new PublicKey(loserWallet),
     treasuryWallet: authorityPubkey,
     mint,
+    forfeitReason: args.forfeitReason ?? "disconnect",
+    rentToWinner: false,
   });
   transaction.add(settleIx);
 
@@ -1,5 +1,72 @@
-/// 10% treasury fee = 1000 basis points
+use anchor_lang::prelude::*;
+
+/// Default 10% treasury fee = 1000 basis points, used when init omits `fee_bps`
 pub const FEE_BPS: u16 = 1000;
 
+/// Highest treasury fee an escrow may be initialized with (20%)
+pub const MAX_FEE_BPS: u16 = 2000;
+
+/// Default native SOL wager bounds (0.001 SOL .. 100 SOL), applied per player.
+///
+/// These are lamport values and only make sense for native SOL escrows.
+/// SPL mints have their own decimals (USDC: 1 USDC = 1_000_000 base units),
+/// so SPL escrows pass `min_wager`/`max_wager` at init instead.
+pub const MIN_WAGER_LAMPORTS: u64 = 1_000_000;
+pub const MAX_WAGER_LAMPORTS: u64 = 100_000_000_000;
+
+/// Seconds a committed result has to be revealed before it lapses
+pub const REVEAL_WINDOW_SECS: i64 = 60 * 60;
+
+/// Seconds players have to dispute a result proposed by `settle_with_challenge`
+pub const CHALLENGE_WINDOW_SECS: i64 = 60 * 60;
+
+/// Longest an escrow can hold funds before anyone may `expire_refund` it
+pub const MAX_ESCROW_LIFETIME_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Current `MatchEscrow` layout version. Bump when appending fields and
+/// teach `migrate_escrow` how to fill them.
+pub const ESCROW_VERSION: u8 = 26;
+
 /// PDA seed prefix for escrow accounts
 pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// PDA seed for the program-wide `ProgramConfig` singleton
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// PDA seed prefix for multiplayer (free-for-all) escrow accounts
+pub const MULTI_ESCROW_SEED: &[u8] = b"multi_escrow";
+
+/// Most players a multiplayer escrow can seat
+pub const MAX_PLAYERS: usize = 8;
+
+/// PDA seed prefix for cross-asset (two-mint) escrow accounts
+pub const CROSS_ESCROW_SEED: &[u8] = b"cross_escrow";
+
+/// PDA seed prefix for per-round-stake series escrow accounts
+pub const SERIES_ESCROW_SEED: &[u8] = b"series_escrow";
+
+/// Most rounds a series escrow can stake
+pub const MAX_SERIES_ROUNDS: usize = 9;
+
+/// PDA seed prefix for per-treasury `ResultLog` accounts
+pub const RESULT_LOG_SEED: &[u8] = b"result_log";
+
+/// Most recent results a `ResultLog` keeps before overwriting the oldest
+pub const RESULT_LOG_CAPACITY: usize = 16;
+
+/// PDA seed prefix for per-treasury `FeeVault` accounts
+pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+
+/// PDA seed prefix for per-wallet `PlayerIndex` pages
+pub const PLAYER_INDEX_SEED: &[u8] = b"player_index";
+
+/// Active lobbies one `PlayerIndex` page can list
+pub const PLAYER_INDEX_CAPACITY: usize = 16;
+
+/// Native ed25519 signature verification program
+pub const ED25519_PROGRAM_ID: Pubkey = pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+/// Metaplex Token Metadata program, owner of the metadata accounts read
+/// when an escrow gates entry on an NFT collection
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
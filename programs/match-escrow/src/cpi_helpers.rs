@@ -0,0 +1,104 @@
+//! Typed wrappers over the Anchor-generated `cpi` module for programs that
+//! compose with the escrow (e.g. a match orchestrator). They fill in the
+//! optional accounts so callers only pass what the native SOL path needs;
+//! SPL callers use `crate::cpi` directly with the token accounts set.
+//!
+//! Signer seeds are forwarded for callers whose depositor or settle
+//! authority is one of their own PDAs.
+
+use anchor_lang::prelude::*;
+use crate::cpi::{self, accounts};
+use crate::instructions::EscrowSnapshot;
+
+/// Deposits `depositor`'s wager into a native SOL escrow.
+pub fn deposit_native<'info>(
+    program: AccountInfo<'info>,
+    depositor: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    config: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    cpi::deposit(CpiContext::new_with_signer(
+        program,
+        accounts::Deposit {
+            depositor,
+            escrow,
+            config,
+            authority: None,
+            delegate: None,
+            depositor_token_account: None,
+            escrow_token_account: None,
+            mint: None,
+            token_program: None,
+            entry_nft_token_account: None,
+            entry_nft_metadata: None,
+            player_index: None,
+            system_program,
+        },
+        signer_seeds,
+    ))
+}
+
+/// Accounts for [`settle_native`]. `config` is only needed when the escrow
+/// uses the global treasury.
+pub struct SettleNative<'info> {
+    pub settle_authority: AccountInfo<'info>,
+    pub escrow: AccountInfo<'info>,
+    pub rent_payer: AccountInfo<'info>,
+    pub winner: AccountInfo<'info>,
+    pub treasury: AccountInfo<'info>,
+    pub config: Option<AccountInfo<'info>>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Pays out a native SOL escrow to `winner`, returning rent to the rent
+/// payer. Escrows with a referrer need the referrer account and must go
+/// through `crate::cpi::settle`.
+pub fn settle_native<'info>(
+    program: AccountInfo<'info>,
+    accts: SettleNative<'info>,
+    winner: Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    cpi::settle(
+        CpiContext::new_with_signer(
+            program,
+            accounts::Settle {
+                settle_authority: accts.settle_authority,
+                payout: accounts::Payout {
+                    escrow: accts.escrow,
+                    rent_payer: accts.rent_payer,
+                    winner: accts.winner,
+                    treasury: accts.treasury,
+                    winner_token_account: None,
+                    treasury_token_account: None,
+                    escrow_token_account: None,
+                    mint: None,
+                    token_program: None,
+                    config: accts.config,
+                    referrer: None,
+                    referrer_token_account: None,
+                    result_log: None,
+                    fee_vault: None,
+                    host_index: None,
+                    opponent_index: None,
+                    payout_recipient: None,
+                    system_program: accts.system_program,
+                },
+            },
+            signer_seeds,
+        ),
+        winner,
+        false,
+    )
+}
+
+/// Reads the escrow's status via `get_state` return data.
+pub fn get_state<'info>(
+    program: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+) -> Result<EscrowSnapshot> {
+    let ret = cpi::get_state(CpiContext::new(program, accounts::GetState { escrow }))?;
+    Ok(ret.get())
+}
@@ -1,21 +1,203 @@
 use anchor_lang::prelude::*;
 
+/// Program errors. Clients see each variant as custom error code
+/// `6000 + discriminant` (e.g. `AlreadySettled` is 6003). The discriminants
+/// are explicit and permanent: never reorder or renumber a variant, and
+/// give new variants the next unused number at the end.
 #[error_code]
 pub enum EscrowError {
     #[msg("Player has already deposited")]
-    AlreadyDeposited,
+    AlreadyDeposited = 0,
     #[msg("Caller is not the authority")]
-    NotAuthorized,
+    NotAuthorized = 1,
     #[msg("Both players haven't deposited yet")]
-    EscrowNotFunded,
+    EscrowNotFunded = 2,
     #[msg("Escrow already settled")]
-    AlreadySettled,
+    AlreadySettled = 3,
     #[msg("Winner is neither host nor opponent")]
-    InvalidWinner,
+    InvalidWinner = 4,
     #[msg("Forfeiter is neither host nor opponent")]
-    InvalidForfeiter,
+    InvalidForfeiter = 5,
     #[msg("Escrow doesn't have enough funds")]
-    InsufficientFunds,
+    InsufficientFunds = 6,
     #[msg("Required SPL account is missing")]
-    MissingSplAccount,
+    MissingSplAccount = 7,
+    #[msg("Deposit deadline has passed")]
+    DepositWindowClosed = 8,
+    #[msg("Deposit deadline has not passed yet")]
+    DepositWindowOpen = 9,
+    #[msg("Escrow is fully funded")]
+    AlreadyFunded = 10,
+    #[msg("No deposit to refund")]
+    NoDepositToRefund = 11,
+    #[msg("Treasury fee exceeds the maximum allowed")]
+    FeeTooHigh = 12,
+    #[msg("Cannot cancel an escrow after a deposit has landed")]
+    CannotCancelFunded = 13,
+    #[msg("Token account mint does not match the escrow mint")]
+    WrongMint = 14,
+    #[msg("Escrow token account is not owned by the escrow PDA")]
+    InvalidEscrowTokenAccount = 15,
+    #[msg("Escrow received less than the wager amount")]
+    DepositShortfall = 16,
+    #[msg("Match deadline has not passed yet")]
+    MatchStillLive = 17,
+    #[msg("Rounds to win must be at least 1")]
+    InvalidRoundsToWin = 18,
+    #[msg("Series has already been decided")]
+    SeriesComplete = 19,
+    #[msg("Winner has not won enough rounds")]
+    SeriesNotDecided = 20,
+    #[msg("Escrow is under dispute")]
+    Disputed = 21,
+    #[msg("Escrow is not under dispute")]
+    NotDisputed = 22,
+    #[msg("Revealed result does not match the commitment")]
+    BadReveal = 23,
+    #[msg("A result has already been committed")]
+    CommitmentExists = 24,
+    #[msg("No result has been committed")]
+    NoCommitment = 25,
+    #[msg("Reveal deadline has passed")]
+    RevealWindowClosed = 26,
+    #[msg("Wager must be non-zero unless the escrow is free entry")]
+    ZeroWager = 27,
+    #[msg("Wager is below the minimum")]
+    WagerTooSmall = 28,
+    #[msg("Wager is above the maximum")]
+    WagerTooLarge = 29,
+    #[msg("Escrow has not been settled")]
+    NotSettled = 30,
+    #[msg("Payment reference must be non-zero")]
+    MissingPaymentRef = 31,
+    #[msg("Payment reference does not match the confirmed deposit")]
+    PaymentRefMismatch = 32,
+    #[msg("Deposit and match deadlines must both be slots or both be timestamps")]
+    MixedDeadlineModes = 33,
+    #[msg("Multiplayer escrows need between 2 and 8 distinct players")]
+    InvalidPlayerCount = 34,
+    #[msg("Player index does not match the signer's seat")]
+    InvalidPlayerIndex = 35,
+    #[msg("Payout table is malformed or does not sum to the pot minus fee")]
+    InvalidPayoutTable = 36,
+    #[msg("Program is paused")]
+    ProgramPaused = 37,
+    #[msg("Program config account is required for this escrow")]
+    MissingConfig = 38,
+    #[msg("Host and opponent must be distinct, non-default wallets")]
+    DuplicatePlayer = 39,
+    #[msg("Batch settlement only supports native SOL escrows")]
+    NativeOnly = 40,
+    #[msg("Batch accounts must come in (escrow, winner, rent_payer) triples")]
+    InvalidBatch = 41,
+    #[msg("Escrow token account holds no leftover balance")]
+    NoStuckFunds = 42,
+    #[msg("Escrow account version is not supported; migrate it first")]
+    UnsupportedVersion = 43,
+    #[msg("Escrow account is already at the current version")]
+    AlreadyMigrated = 44,
+    #[msg("Settlement already in progress")]
+    SettlementInProgress = 45,
+    #[msg("Settlement deadline has not passed yet")]
+    SettlementWindowOpen = 46,
+    #[msg("Cannot modify an escrow after a deposit has landed")]
+    CannotModifyFunded = 47,
+    #[msg("Missing or invalid oracle signature over the match result")]
+    InvalidOracleSig = 48,
+    #[msg("Referrer account is missing or does not match the escrow")]
+    InvalidReferrer = 49,
+    #[msg("Escrow would drop below its rent-exempt minimum")]
+    BelowRentExempt = 50,
+    #[msg("Unknown fee rounding mode")]
+    InvalidRoundingMode = 51,
+    #[msg("Only the recorded winner can claim this payout")]
+    NotWinner = 52,
+    #[msg("No payout is waiting to be claimed")]
+    NothingToClaim = 53,
+    #[msg("Escrow still holds an unclaimed payout")]
+    PayoutPending = 54,
+    #[msg("Depositor must hold a verified NFT from the required collection")]
+    MissingEntryNft = 55,
+    #[msg("A settlement is already awaiting its challenge window")]
+    SettlementProposed = 56,
+    #[msg("No settlement has been proposed")]
+    NoProposedSettlement = 57,
+    #[msg("The challenge window is still open")]
+    ChallengeWindowOpen = 58,
+    #[msg("Token account is not owned by the expected player")]
+    WrongTokenOwner = 59,
+    #[msg("Escrow has not reached its maximum lifetime")]
+    NotYetExpired = 60,
+    #[msg("Bonuses can only be added to a funded, unsettled match")]
+    BonusNotAccepted = 61,
+    #[msg("Escrow is not fully funded or has already settled")]
+    NotFunded = 62,
+    #[msg("Unknown forfeit reason")]
+    InvalidForfeitReason = 63,
+    #[msg("Treasury cannot be a player or the escrow itself")]
+    InvalidTreasury = 64,
+    #[msg("Fee burning needs a burnable SPL mint")]
+    BurnRequiresSpl = 65,
+    #[msg("Escrow cannot move to that state from its current one")]
+    InvalidStateTransition = 66,
+    #[msg("Winnings have already been claimed")]
+    AlreadyClaimed = 67,
+    #[msg("Result log does not belong to the escrow's treasury")]
+    InvalidResultLog = 68,
+    #[msg("Opponent seat is already taken")]
+    SlotTaken = 69,
+    #[msg("No deposit order was recorded for this escrow")]
+    NoDepositOrder = 70,
+    #[msg("Cross-asset escrows need a different mint for each player")]
+    SameMint = 71,
+    #[msg("max_fee must be 0 or at least min_fee")]
+    InvalidFeeBounds = 72,
+    #[msg("confirm_nonce must exceed the last accepted nonce")]
+    StaleConfirmNonce = 73,
+    #[msg("Agreed shares must add up to the pot less the fee")]
+    InvalidSplit = 74,
+    #[msg("Minimum match duration has not elapsed since funding")]
+    MatchTooShort = 75,
+    #[msg("Emergency refunds are only available while the program is paused")]
+    NotPaused = 76,
+    #[msg("Deposit would exceed the player's wager")]
+    ExceedsWager = 77,
+    #[msg("Installment deposits are not supported for mints with a transfer fee")]
+    InstallmentsUnsupported = 78,
+    #[msg("Fee vault does not belong to the escrow's treasury")]
+    InvalidFeeVault = 79,
+    #[msg("Fee vault has nothing to sweep")]
+    NoFeesToSweep = 80,
+    #[msg("Winner and treasury must be different accounts")]
+    WinnerIsTreasury = 81,
+    #[msg("Player index page is full; use another page")]
+    PlayerIndexFull = 82,
+    #[msg("Player index belongs to a different wallet")]
+    WrongPlayerIndex = 83,
+    #[msg("Escrow token account has a delegate set")]
+    EscrowDelegated = 84,
+    #[msg("Round index is not the next round to report")]
+    InvalidRound = 85,
+    #[msg("Not every round of the series has been reported")]
+    SeriesIncomplete = 86,
+    #[msg("Escrow has no default winner to crank-settle to")]
+    NoDefaultWinner = 87,
+    #[msg("Default winner must be a seated player on an escrow without referral or burn")]
+    InvalidDefaultWinner = 88,
+    #[msg("Escrow has a default winner; use crank_settle")]
+    HasDefaultWinner = 89,
+    #[msg("Joining this escrow requires the authority's approval")]
+    JoinNotApproved = 90,
+    #[msg("Redirecting the payout requires the winner's signature")]
+    RecipientNotApproved = 91,
+    #[msg("Payer cannot cover the escrow's rent")]
+    InsufficientRent = 92,
+    #[msg("Token account is frozen; thaw it and retry")]
+    TokenAccountFrozen = 93,
+    #[msg("Sponsored escrows are funded only by sponsor_deposit and cannot be refunded to the players")]
+    SponsoredEscrow = 94,
+    #[msg("Escrow is not sponsored")]
+    NotSponsored = 95,
+    #[msg("Seat is already being paid by the other deposit method")]
+    DepositMethodMismatch = 96,
 }
@@ -0,0 +1,501 @@
+use anchor_lang::prelude::*;
+
+/// Carries the escrow PDA and its bump alongside `lobby_id_hash`, so
+/// clients and indexers can cache the mapping from the init transaction
+/// instead of re-deriving it.
+#[event]
+pub struct EscrowInitialized {
+    pub lobby_id_hash: [u8; 32],
+    pub escrow: Pubkey,
+    pub bump: u8,
+    pub host: Pubkey,
+    pub opponent: Pubkey,
+    pub host_wager: u64,
+    pub opponent_wager: u64,
+    pub token_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+    pub deposit_deadline: i64,
+    pub fee_bps: u16,
+    pub match_deadline: i64,
+    pub rounds_to_win: u8,
+    pub free_entry: bool,
+    pub retain_record: bool,
+    pub slot_deadlines: bool,
+    pub deposit_deadline_slot: u64,
+    pub match_deadline_slot: u64,
+    pub use_global_treasury: bool,
+    pub require_authorized_deposit: bool,
+    pub rent_payer: Pubkey,
+    pub match_ref: [u8; 32],
+    pub settlement_deadline: i64,
+    pub oracle: Pubkey,
+    pub referrer: Pubkey,
+    pub referral_bps: u16,
+    pub rounding: u8,
+    pub required_collection: Pubkey,
+    pub settle_authority: Pubkey,
+    pub burn_bps: u16,
+    pub min_fee: u64,
+    pub max_fee: u64,
+    pub min_match_duration: i64,
+    pub default_winner: Pubkey,
+    pub crank_reward_bps: u16,
+    pub require_join_approval: bool,
+    pub sponsored: bool,
+}
+
+#[event]
+pub struct DepositMade {
+    pub lobby_id_hash: [u8; 32],
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SponsorDeposited {
+    pub lobby_id_hash: [u8; 32],
+    pub sponsor: Pubkey,
+    /// Base units credited to the host's and opponent's seats
+    pub host_amount: u64,
+    pub opponent_amount: u64,
+}
+
+#[event]
+pub struct DepositConfirmed {
+    pub lobby_id_hash: [u8; 32],
+    pub depositor: Pubkey,
+    pub payment_ref: [u8; 32],
+    pub confirm_nonce: u64,
+}
+
+#[event]
+pub struct EscrowSettled {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub fee: u64,
+    /// Wallet the payout went to; differs from `winner` when redirected
+    pub recipient: Pubkey,
+}
+
+/// Emitted when a payout closes the escrow, so a later settle that fails
+/// on the missing account can be told apart from one that never existed.
+#[event]
+pub struct EscrowClosed {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+}
+
+#[event]
+pub struct EscrowForfeited {
+    pub lobby_id_hash: [u8; 32],
+    pub forfeiter: Pubkey,
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub fee: u64,
+    /// `ForfeitReason` discriminant
+    pub reason: u8,
+}
+
+#[event]
+pub struct EscrowDrawn {
+    pub lobby_id_hash: [u8; 32],
+    pub host: Pubkey,
+    pub opponent: Pubkey,
+    pub host_share: u64,
+    pub opponent_share: u64,
+    /// Treasury fee including any odd-lamport remainder from the split
+    pub fee: u64,
+}
+
+#[event]
+pub struct EscrowReclaimed {
+    pub lobby_id_hash: [u8; 32],
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EscrowCancelled {
+    pub lobby_id_hash: [u8; 32],
+}
+
+#[event]
+pub struct TimeoutWinClaimed {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct RoundReported {
+    pub lobby_id_hash: [u8; 32],
+    pub round_winner: Pubkey,
+    pub host_wins: u8,
+    pub opponent_wins: u8,
+}
+
+#[event]
+pub struct DisputeRaised {
+    pub lobby_id_hash: [u8; 32],
+    pub raised_by: Pubkey,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub lobby_id_hash: [u8; 32],
+}
+
+#[event]
+pub struct ResultCommitted {
+    pub lobby_id_hash: [u8; 32],
+    pub committer: Pubkey,
+    pub reveal_deadline: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub lobby_id_hash: [u8; 32],
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct SettlementQuote {
+    pub lobby_id_hash: [u8; 32],
+    pub total_pot: u64,
+    pub fee: u64,
+    pub payout: u64,
+}
+
+#[event]
+pub struct NoShowRefunded {
+    pub lobby_id_hash: [u8; 32],
+    pub depositor: Pubkey,
+    pub refund: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct EscrowSwept {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub settled_at: i64,
+}
+
+#[event]
+pub struct MultiEscrowInitialized {
+    pub lobby_id_hash: [u8; 32],
+    pub players: Vec<Pubkey>,
+    pub wager: u64,
+    pub token_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+    pub deposit_deadline: i64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct MultiDepositMade {
+    pub lobby_id_hash: [u8; 32],
+    pub player_index: u8,
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MultiEscrowSettled {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct CrossEscrowInitialized {
+    pub lobby_id_hash: [u8; 32],
+    pub host: Pubkey,
+    pub opponent: Pubkey,
+    pub host_mint: Pubkey,
+    pub opponent_mint: Pubkey,
+    pub host_wager: u64,
+    pub opponent_wager: u64,
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+    pub deposit_deadline: i64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct CrossDepositMade {
+    pub lobby_id_hash: [u8; 32],
+    pub depositor: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Amounts are per asset: `host_asset_*` in `host_mint`, `opponent_asset_*`
+/// in `opponent_mint`.
+#[event]
+pub struct CrossEscrowSettled {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub host_asset_payout: u64,
+    pub host_asset_fee: u64,
+    pub opponent_asset_payout: u64,
+    pub opponent_asset_fee: u64,
+}
+
+#[event]
+pub struct CrossEscrowForfeited {
+    pub lobby_id_hash: [u8; 32],
+    pub forfeiter: Pubkey,
+    pub winner: Pubkey,
+    pub host_asset_payout: u64,
+    pub host_asset_fee: u64,
+    pub opponent_asset_payout: u64,
+    pub opponent_asset_fee: u64,
+}
+
+#[event]
+pub struct MultiEscrowRanked {
+    pub lobby_id_hash: [u8; 32],
+    pub finishers: Vec<Pubkey>,
+    pub payouts: Vec<u64>,
+    pub fee: u64,
+}
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct PausedSet {
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct TreasurySet {
+    pub admin: Pubkey,
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+}
+
+#[event]
+pub struct StuckFundsWithdrawn {
+    pub lobby_id_hash: [u8; 32],
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EscrowMigrated {
+    pub lobby_id_hash: [u8; 32],
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct EscrowAutoRefunded {
+    pub lobby_id_hash: [u8; 32],
+    pub host_refund: u64,
+    pub opponent_refund: u64,
+}
+
+#[event]
+pub struct EscrowCranked {
+    pub lobby_id_hash: [u8; 32],
+    pub cranker: Pubkey,
+    pub winner: Pubkey,
+    pub payout: u64,
+    /// Total fee, including the cranker's reward
+    pub fee: u64,
+    pub crank_reward: u64,
+}
+
+#[event]
+pub struct BonusAdded {
+    pub lobby_id_hash: [u8; 32],
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub bonus_pool: u64,
+}
+
+#[event]
+pub struct EscrowExpired {
+    pub lobby_id_hash: [u8; 32],
+    pub host_refund: u64,
+    pub opponent_refund: u64,
+}
+
+#[event]
+pub struct WagerUpdated {
+    pub lobby_id_hash: [u8; 32],
+    pub old_host_wager: u64,
+    pub old_opponent_wager: u64,
+    pub host_wager: u64,
+    pub opponent_wager: u64,
+}
+
+#[event]
+pub struct DoubleForfeitSettled {
+    pub lobby_id_hash: [u8; 32],
+    pub host_refund: u64,
+    pub opponent_refund: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct FeeBurned {
+    pub lobby_id_hash: [u8; 32],
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralPaid {
+    pub lobby_id_hash: [u8; 32],
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EscrowSettlePending {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct WinningsClaimed {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SettlementProposed {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub challenge_deadline: i64,
+}
+
+#[event]
+pub struct ResultLogged {
+    pub treasury: Pubkey,
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct OpponentJoined {
+    pub lobby_id_hash: [u8; 32],
+    pub opponent: Pubkey,
+}
+
+#[event]
+pub struct TiebreakSettled {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct EscrowAgreedSettled {
+    pub lobby_id_hash: [u8; 32],
+    pub host_share: u64,
+    pub opponent_share: u64,
+    /// Treasury fee including any rounding remainder from the split
+    pub fee: u64,
+}
+
+#[event]
+pub struct MutualCancelled {
+    pub lobby_id_hash: [u8; 32],
+    pub host_refund: u64,
+    pub opponent_refund: u64,
+}
+
+#[event]
+pub struct EscrowEmergencyRefunded {
+    pub lobby_id_hash: [u8; 32],
+    pub admin: Pubkey,
+    pub host_refund: u64,
+    pub opponent_refund: u64,
+}
+
+/// `mint` is `Pubkey::default()` for native SOL fees.
+#[event]
+pub struct FeesAccrued {
+    pub treasury: Pubkey,
+    pub lobby_id_hash: [u8; 32],
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeesSwept {
+    pub treasury: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlayerIndexAdded {
+    pub player: Pubkey,
+    pub lobby_id_hash: [u8; 32],
+}
+
+#[event]
+pub struct PlayerIndexRemoved {
+    pub player: Pubkey,
+    pub lobby_id_hash: [u8; 32],
+}
+
+#[event]
+pub struct SeriesEscrowInitialized {
+    pub lobby_id_hash: [u8; 32],
+    pub host: Pubkey,
+    pub opponent: Pubkey,
+    pub round_wagers: Vec<u64>,
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+    pub deposit_deadline: i64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct SeriesDepositMade {
+    pub lobby_id_hash: [u8; 32],
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SeriesRoundReported {
+    pub lobby_id_hash: [u8; 32],
+    pub round: u8,
+    pub round_winner: Pubkey,
+    /// Both players' stake for the round, credited to `round_winner`
+    pub pot: u64,
+    pub host_tally: u64,
+    pub opponent_tally: u64,
+}
+
+#[event]
+pub struct SeriesEscrowSettled {
+    pub lobby_id_hash: [u8; 32],
+    pub host_payout: u64,
+    pub opponent_payout: u64,
+    pub fee: u64,
+}
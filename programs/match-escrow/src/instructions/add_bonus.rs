@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::BonusAdded;
+use crate::instructions::deposit::net_of_transfer_fee;
+use crate::state::MatchEscrow;
+
+/// Lets any signer sweeten a funded match. The bonus joins the pot, so the
+/// winner receives it less the usual fee; if the match is instead refunded,
+/// it goes back to the players in proportion to their stakes. Bonuses are
+/// not returned to the sponsor.
+#[derive(Accounts)]
+pub struct AddBonus<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// Sponsor's token account (only needed for SPL escrows).
+    #[account(mut)]
+    pub sponsor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account, only needed for SPL escrows. Must be the
+    /// escrow PDA's associated token account.
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL escrows.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL escrows.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddBonus>, amount: u64) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all needed values BEFORE any CPI calls.
+    // ---------------------------------------------------------------
+    let both_funded = ctx.accounts.escrow.both_funded();
+    let settled = ctx.accounts.escrow.settled;
+    let disputed = ctx.accounts.escrow.disputed;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let escrow_key = ctx.accounts.escrow.key();
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(amount > 0, EscrowError::ZeroWager);
+    require!(
+        both_funded && !settled && !disputed,
+        EscrowError::BonusNotAccepted
+    );
+
+    // ---------------------------------------------------------------
+    // Transfer the bonus into the escrow
+    // ---------------------------------------------------------------
+    let received = if is_native {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.sponsor.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        amount
+    } else {
+        let sponsor_ta = ctx
+            .accounts
+            .sponsor_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(sponsor_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &escrow_key,
+                    &token_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+
+        let expected = net_of_transfer_fee(mint, amount)?;
+        let balance_before = escrow_ta.amount;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_prog.to_account_info(),
+                TransferChecked {
+                    from: sponsor_ta.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: escrow_ta.to_account_info(),
+                    authority: ctx.accounts.sponsor.to_account_info(),
+                },
+            ),
+            amount,
+            mint.decimals,
+        )?;
+
+        // Credit only what landed, as `deposit` does
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        escrow_ta.reload()?;
+        let received = escrow_ta
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        require!(received == expected, EscrowError::DepositShortfall);
+        received
+    };
+
+    // ---------------------------------------------------------------
+    // Record the bonus (mutable borrow begins here, after all CPI)
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.bonus_pool = escrow
+        .bonus_pool
+        .checked_add(received)
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    emit!(BonusAdded {
+        lobby_id_hash,
+        sponsor: ctx.accounts.sponsor.key(),
+        amount: received,
+        bonus_pool: escrow.bonus_pool,
+    });
+
+    Ok(())
+}
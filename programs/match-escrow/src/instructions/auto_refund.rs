@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::EscrowAutoRefunded;
+use crate::state::{EscrowStatus, MatchEscrow};
+
+/// Permissionless backstop for a fully funded match that was never
+/// reported (e.g. the server went down). Once `settlement_deadline` has
+/// passed, anyone can return exactly what each player deposited (plus their
+/// share of any bonus), with no fee, and close the escrow to its rent payer.
+#[derive(Accounts)]
+pub struct AutoRefund<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = host @ EscrowError::NotAuthorized,
+        has_one = opponent @ EscrowError::NotAuthorized,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
+        close = rent_payer,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; receives the host's wager.
+    #[account(mut)]
+    pub host: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; receives the opponent's wager.
+    #[account(mut)]
+    pub opponent: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// Host's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Opponent's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL refunds.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL refunds.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AutoRefund>) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    let host_deposited = ctx.accounts.escrow.host_deposited;
+    let opponent_deposited = ctx.accounts.escrow.opponent_deposited;
+    let disputed = ctx.accounts.escrow.disputed;
+    let default_winner = ctx.accounts.escrow.default_winner;
+    let settled = ctx.accounts.escrow.settled;
+    let sponsored = ctx.accounts.escrow.sponsored;
+    let (host_wager, opponent_wager) = ctx.accounts.escrow.refund_amounts()?;
+    let total_pot = ctx.accounts.escrow.total_pot()?;
+    let settlement_deadline = ctx.accounts.escrow.settlement_deadline;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(!settled, EscrowError::AlreadySettled);
+    // A sponsored stake is the authority's, not the players'
+    require!(!sponsored, EscrowError::SponsoredEscrow);
+    require!(!disputed, EscrowError::Disputed);
+    // The players committed to a default outcome up front
+    require!(
+        default_winner == Pubkey::default(),
+        EscrowError::HasDefaultWinner
+    );
+    require!(
+        host_deposited && opponent_deposited,
+        EscrowError::EscrowNotFunded
+    );
+    require!(
+        Clock::get()?.unix_timestamp > settlement_deadline,
+        EscrowError::SettlementWindowOpen
+    );
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Return each stake in full
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports are reclaimed by `close = rent_payer`.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let host_info = ctx.accounts.host.to_account_info();
+        let opponent_info = ctx.accounts.opponent.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= host_wager;
+        **host_info.try_borrow_mut_lamports()? += host_wager;
+
+        **escrow_info.try_borrow_mut_lamports()? -= opponent_wager;
+        **opponent_info.try_borrow_mut_lamports()? += opponent_wager;
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let host_ta = ctx
+            .accounts
+            .host_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let opponent_ta = ctx
+            .accounts
+            .opponent_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        // Refunds must land with the players themselves
+        require!(host_ta.owner == host, EscrowError::NotAuthorized);
+        require!(opponent_ta.owner == opponent, EscrowError::NotAuthorized);
+        require!(host_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(opponent_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        for (to, amount) in [
+            (host_ta.to_account_info(), host_wager),
+            (opponent_ta.to_account_info(), opponent_wager),
+        ] {
+            // Free-entry seats may have staked nothing
+            if amount == 0 {
+                continue;
+            }
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to,
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Mark settled (mutable borrow after all CPI).
+    // The `close = rent_payer` constraint reclaims rent after handler.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Refunded)?;
+
+    emit!(EscrowAutoRefunded {
+        lobby_id_hash,
+        host_refund: host_wager,
+        opponent_refund: opponent_wager,
+    });
+
+    Ok(())
+}
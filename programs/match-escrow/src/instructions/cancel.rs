@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::EscrowCancelled;
+use crate::state::{EscrowStatus, MatchEscrow};
+
+/// Authority-only teardown of an escrow that nobody has funded yet.
+/// Closes the account and returns the init rent to the authority.
+#[derive(Accounts)]
+pub struct CancelEscrow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<CancelEscrow>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(
+        !escrow.host_deposited && !escrow.opponent_deposited && escrow.total_deposited()? == 0,
+        EscrowError::CannotCancelFunded
+    );
+    escrow.transition(EscrowStatus::Refunded)?;
+
+    emit!(EscrowCancelled {
+        lobby_id_hash: escrow.lobby_id_hash,
+    });
+
+    Ok(())
+}
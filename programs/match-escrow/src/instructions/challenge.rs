@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use crate::constants::{CHALLENGE_WINDOW_SECS, ESCROW_SEED};
+use crate::error::EscrowError;
+use crate::events::{EscrowSettled, SettlementProposed};
+use crate::instructions::settle::{pay_winner, Settle};
+use crate::state::MatchEscrow;
+
+/// Optimistic settlement, step one. The settle authority names the winner
+/// but nothing moves: the pot stays locked in the escrow for
+/// `CHALLENGE_WINDOW_SECS`, during which either player can `raise_dispute`.
+/// Resolving that dispute discards the proposal.
+#[derive(Accounts)]
+pub struct SettleWithChallenge<'info> {
+    pub settle_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = settle_authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn propose_handler(ctx: Context<SettleWithChallenge>, winner: Pubkey) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(escrow.is_player(&winner), EscrowError::InvalidWinner);
+    require!(escrow.both_funded(), EscrowError::EscrowNotFunded);
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(!escrow.disputed, EscrowError::Disputed);
+    require!(
+        escrow.proposed_winner == Pubkey::default(),
+        EscrowError::SettlementProposed
+    );
+    if escrow.rounds_to_win > 1 {
+        let wins = if winner == escrow.host {
+            escrow.host_wins
+        } else {
+            escrow.opponent_wins
+        };
+        require!(
+            wins >= escrow.rounds_to_win,
+            EscrowError::SeriesNotDecided
+        );
+    }
+
+    let challenge_deadline = Clock::get()?
+        .unix_timestamp
+        .checked_add(CHALLENGE_WINDOW_SECS)
+        .ok_or(EscrowError::ChallengeWindowOpen)?;
+
+    escrow.proposed_winner = winner;
+    escrow.challenge_deadline = challenge_deadline;
+
+    emit!(SettlementProposed {
+        lobby_id_hash: escrow.lobby_id_hash,
+        winner,
+        challenge_deadline,
+    });
+
+    Ok(())
+}
+
+/// Optimistic settlement, step two. Once the challenge window has closed
+/// without a dispute, pays the proposed winner exactly as `settle` would.
+pub fn finalize_handler(ctx: Context<Settle>) -> Result<()> {
+    let winner = ctx.accounts.payout.escrow.proposed_winner;
+    require!(
+        winner != Pubkey::default(),
+        EscrowError::NoProposedSettlement
+    );
+
+    let (payout, fee) = pay_winner(&mut ctx.accounts.payout, winner, false)?;
+
+    emit!(EscrowSettled {
+        lobby_id_hash: ctx.accounts.payout.escrow.lobby_id_hash,
+        winner,
+        payout,
+        fee,
+        recipient: ctx.accounts.payout.recipient(winner),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use crate::error::EscrowError;
+use crate::events::TimeoutWinClaimed;
+use crate::instructions::settle::{pay_winner, Settle};
+
+/// Settle-authority-only payout to the surviving player once the match
+/// deadline has passed without a result. Uses the same accounts and
+/// distribution as `settle`, so the escrow is closed to its rent payer
+/// afterwards.
+pub fn handler(ctx: Context<Settle>, winner: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.payout.escrow.match_deadline_passed(&Clock::get()?),
+        EscrowError::MatchStillLive
+    );
+
+    let (payout, fee) = pay_winner(&mut ctx.accounts.payout, winner, false)?;
+
+    emit!(TimeoutWinClaimed {
+        lobby_id_hash: ctx.accounts.payout.escrow.lobby_id_hash,
+        winner,
+        payout,
+        fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::constants::{ESCROW_SEED, REVEAL_WINDOW_SECS};
+use crate::error::EscrowError;
+use crate::events::{EscrowSettled, ResultCommitted};
+use crate::instructions::settle::*;
+use crate::state::MatchEscrow;
+
+/// Phase one of trustless settlement: a player commits to
+/// `sha256(winner || nonce)` without revealing the winner, so an observer
+/// can't react to a pending result. The committed winner must be the
+/// committer's opponent, which makes the commitment a sealed concession.
+#[derive(Accounts)]
+pub struct CommitResult<'info> {
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+/// Phase two: the settle authority reveals the preimage before
+/// `reveal_deadline` and the pot is paid out exactly as in `settle`. The
+/// authority submits the transaction but can only pay the winner the
+/// committing player conceded to.
+#[derive(Accounts)]
+pub struct RevealResult<'info> {
+    pub settle: Settle<'info>,
+}
+
+pub fn commit_handler(ctx: Context<CommitResult>, commitment: [u8; 32]) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(
+        player == escrow.host || player == escrow.opponent,
+        EscrowError::NotAuthorized
+    );
+    require!(
+        escrow.host_deposited && escrow.opponent_deposited,
+        EscrowError::EscrowNotFunded
+    );
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(!escrow.disputed, EscrowError::Disputed);
+    require!(commitment != [0u8; 32], EscrowError::BadReveal);
+    require!(
+        escrow.result_commitment == [0u8; 32],
+        EscrowError::CommitmentExists
+    );
+
+    let reveal_deadline = Clock::get()?
+        .unix_timestamp
+        .checked_add(REVEAL_WINDOW_SECS)
+        .ok_or(EscrowError::RevealWindowClosed)?;
+
+    escrow.result_commitment = commitment;
+    escrow.result_committer = player;
+    escrow.reveal_deadline = reveal_deadline;
+
+    emit!(ResultCommitted {
+        lobby_id_hash: escrow.lobby_id_hash,
+        committer: player,
+        reveal_deadline,
+    });
+
+    Ok(())
+}
+
+pub fn reveal_handler(ctx: Context<RevealResult>, winner: Pubkey, nonce: [u8; 32]) -> Result<()> {
+    let escrow = &ctx.accounts.settle.payout.escrow;
+    let lobby_id_hash = escrow.lobby_id_hash;
+
+    // ---------------------------------------------------------------
+    // Validation; `pay_winner` checks the winner, funding and accounts
+    // ---------------------------------------------------------------
+    require!(
+        escrow.result_commitment != [0u8; 32],
+        EscrowError::NoCommitment
+    );
+    require!(
+        Clock::get()?.unix_timestamp <= escrow.reveal_deadline,
+        EscrowError::RevealWindowClosed
+    );
+    require!(
+        hashv(&[winner.as_ref(), nonce.as_ref()]).to_bytes() == escrow.result_commitment,
+        EscrowError::BadReveal
+    );
+    // A commitment is a concession: the committer can never award themselves.
+    require!(winner != escrow.result_committer, EscrowError::BadReveal);
+
+    let (payout, fee) = pay_winner(&mut ctx.accounts.settle.payout, winner, false)?;
+
+    emit!(EscrowSettled {
+        lobby_id_hash,
+        winner,
+        payout,
+        fee,
+        recipient: ctx.accounts.settle.payout.recipient(winner),
+    });
+
+    Ok(())
+}
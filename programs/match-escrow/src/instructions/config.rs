@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::constants::CONFIG_SEED;
+use crate::error::EscrowError;
+use crate::events::{ConfigInitialized, PausedSet, TreasurySet};
+use crate::state::ProgramConfig;
+
+/// Creates the program-wide config singleton. The signer becomes the admin,
+/// so this should be called once right after deployment.
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProgramConfig::INIT_SPACE,
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Admin-only circuit breaker for new escrows and deposits.
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ EscrowError::NotAuthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+/// Admin-only update of the global fee destination. Takes effect for every
+/// open escrow created with `use_global_treasury`.
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ EscrowError::NotAuthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+pub fn initialize_handler(ctx: Context<InitializeConfig>, treasury: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.paused = false;
+    config.treasury = treasury;
+    config.bump = ctx.bumps.config;
+
+    emit!(ConfigInitialized {
+        admin: config.admin,
+        treasury,
+    });
+
+    Ok(())
+}
+
+pub fn set_paused_handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
+
+    emit!(PausedSet {
+        admin: config.admin,
+        paused,
+    });
+
+    Ok(())
+}
+
+pub fn set_treasury_handler(ctx: Context<SetTreasury>, new_treasury: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let old_treasury = config.treasury;
+    config.treasury = new_treasury;
+
+    emit!(TreasurySet {
+        admin: config.admin,
+        old_treasury,
+        new_treasury,
+    });
+
+    Ok(())
+}
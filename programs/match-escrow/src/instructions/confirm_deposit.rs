@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 use crate::constants::ESCROW_SEED;
 use crate::error::EscrowError;
-use crate::state::MatchEscrow;
+use crate::events::DepositConfirmed;
+use crate::state::{DepositMethod, EscrowStatus, MatchEscrow};
 
 /// Authority-only instruction to mark a player's deposit as confirmed
 /// without moving funds onchain.
@@ -9,6 +10,14 @@ use crate::state::MatchEscrow;
 /// Used after x402 payment verification: the joiner pays via the x402
 /// protocol (verified offchain by the facilitator), then the server calls
 /// this instruction to update the onchain deposit flag.
+///
+/// The x402 settlement hash is stored as `payment_ref`. Replaying the same
+/// ref is a no-op success, so server retries are safe; a different ref for
+/// an already-confirmed deposit is rejected.
+///
+/// Every confirm that records a deposit must also carry a `confirm_nonce`
+/// above the escrow's `last_confirm_nonce`, so a duplicated or replayed
+/// instruction from a compromised relay cannot credit a fresh deposit.
 #[derive(Accounts)]
 pub struct ConfirmDeposit<'info> {
     pub authority: Signer<'info>,
@@ -22,22 +31,73 @@ pub struct ConfirmDeposit<'info> {
     pub escrow: Account<'info, MatchEscrow>,
 }
 
-pub fn handler(ctx: Context<ConfirmDeposit>, depositor: Pubkey) -> Result<()> {
+pub fn handler(
+    ctx: Context<ConfirmDeposit>,
+    depositor: Pubkey,
+    payment_ref: [u8; 32],
+    confirm_nonce: u64,
+) -> Result<()> {
     let escrow = &mut ctx.accounts.escrow;
 
+    // An open seat is stored as the default pubkey; it can't be confirmed
+    require!(depositor != Pubkey::default(), EscrowError::NotAuthorized);
+
     let is_host = depositor == escrow.host;
     let is_opponent = depositor == escrow.opponent;
 
     require!(is_host || is_opponent, EscrowError::NotAuthorized);
     require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(!escrow.sponsored, EscrowError::SponsoredEscrow);
+    require!(payment_ref != [0u8; 32], EscrowError::MissingPaymentRef);
+
+    let (deposited, stored_ref) = if is_host {
+        (escrow.host_deposited, escrow.host_payment_ref)
+    } else {
+        (escrow.opponent_deposited, escrow.opponent_payment_ref)
+    };
 
+    if deposited {
+        // Same payment confirmed twice (e.g. a retried server call): no-op.
+        if stored_ref == payment_ref {
+            return Ok(());
+        }
+        // An on-chain `deposit` leaves the ref zeroed.
+        require!(stored_ref == [0u8; 32], EscrowError::PaymentRefMismatch);
+        return err!(EscrowError::AlreadyDeposited);
+    }
+    // A seat part-paid on-chain (installments) can't be topped up by x402
+    escrow.set_deposit_method(is_host, DepositMethod::Confirmed)?;
+    require!(
+        confirm_nonce > escrow.last_confirm_nonce,
+        EscrowError::StaleConfirmNonce
+    );
+    escrow.last_confirm_nonce = confirm_nonce;
+
+    // x402 settles the player's full wager, so credit exactly that
     if is_host {
-        require!(!escrow.host_deposited, EscrowError::AlreadyDeposited);
         escrow.host_deposited = true;
+        escrow.host_deposited_amount = escrow.host_wager;
+        escrow.host_payment_ref = payment_ref;
     } else {
-        require!(!escrow.opponent_deposited, EscrowError::AlreadyDeposited);
         escrow.opponent_deposited = true;
+        escrow.opponent_deposited_amount = escrow.opponent_wager;
+        escrow.opponent_payment_ref = payment_ref;
+    }
+    if escrow.first_depositor == Pubkey::default() {
+        escrow.first_depositor = depositor;
     }
+    let funding = escrow.funding_status();
+    if funding == EscrowStatus::Funded {
+        escrow.funded_at = Clock::get()?.unix_timestamp;
+    }
+    escrow.transition(funding)?;
+
+    emit!(DepositConfirmed {
+        lobby_id_hash: escrow.lobby_id_hash,
+        depositor,
+        payment_ref,
+        confirm_nonce,
+    });
 
     Ok(())
 }
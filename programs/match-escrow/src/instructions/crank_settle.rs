@@ -0,0 +1,285 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION};
+use crate::error::EscrowError;
+use crate::events::{EscrowClosed, EscrowCranked};
+use crate::instructions::deposit::require_undelegated;
+use crate::instructions::settle::close_escrow_ata_if_empty;
+use crate::state::{EscrowStatus, MatchEscrow, ProgramConfig, RoundingMode};
+
+/// Permissionless settlement to the escrow's declared `default_winner` once
+/// `settlement_deadline` has passed without a result. The cranker earns
+/// `crank_reward_bps` of the fee; the rest goes to the treasury as usual.
+/// Escrows without a default winner fall back to `auto_refund` instead.
+#[derive(Accounts)]
+pub struct CrankSettle<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against escrow.default_winner in handler.
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the escrow's fee destination in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Winner's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account (only needed for SPL settlements with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Cranker's token account (only needed for SPL settlements with a reward).
+    #[account(mut)]
+    pub cranker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL settlements.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL settlements.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Program config; only needed when the escrow uses the global treasury.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CrankSettle>) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let winner = ctx.accounts.escrow.default_winner;
+    let both_funded = ctx.accounts.escrow.both_funded();
+    let disputed = ctx.accounts.escrow.disputed;
+    let settled = ctx.accounts.escrow.settled;
+    let settlement_deadline = ctx.accounts.escrow.settlement_deadline;
+    let (total_pot, fee, payout) = ctx.accounts.escrow.compute_distribution()?;
+    let crank_reward = RoundingMode::Floor.apply_bps(fee, ctx.accounts.escrow.crank_reward_bps)?;
+    // Default winners are only allowed without referral or burn, so the
+    // whole fee belongs to the treasury and the cranker
+    let treasury_fee = fee - crank_reward;
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let retain_record = ctx.accounts.escrow.retain_record;
+    let treasury_key = if ctx.accounts.escrow.use_global_treasury {
+        ctx.accounts
+            .config
+            .as_ref()
+            .ok_or(EscrowError::MissingConfig)?
+            .treasury
+    } else {
+        ctx.accounts.escrow.treasury
+    };
+    let cranker = ctx.accounts.cranker.key();
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(winner != Pubkey::default(), EscrowError::NoDefaultWinner);
+    require!(both_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(!disputed, EscrowError::Disputed);
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp > settlement_deadline,
+        EscrowError::SettlementWindowOpen
+    );
+    ctx.accounts.escrow.check_challenge(&winner, &clock)?;
+    require!(
+        ctx.accounts.escrow.version == ESCROW_VERSION,
+        EscrowError::UnsupportedVersion
+    );
+    require!(
+        ctx.accounts.winner.key() == winner,
+        EscrowError::InvalidWinner
+    );
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+    require!(winner != treasury_key, EscrowError::WinnerIsTreasury);
+
+    // Reentrancy guard, as in `pay_winner`
+    ctx.accounts.escrow.begin_settling()?;
+    ctx.accounts.escrow.exit(&crate::ID)?;
+
+    profile!("crank_settle", "validated");
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Transfer funds
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports go to the rent payer when the escrow closes.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let winner_info = ctx.accounts.winner.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+        let cranker_info = ctx.accounts.cranker.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= payout;
+        **winner_info.try_borrow_mut_lamports()? += payout;
+
+        **escrow_info.try_borrow_mut_lamports()? -= treasury_fee;
+        **treasury_info.try_borrow_mut_lamports()? += treasury_fee;
+
+        **escrow_info.try_borrow_mut_lamports()? -= crank_reward;
+        **cranker_info.try_borrow_mut_lamports()? += crank_reward;
+
+        if retain_record {
+            let rent_floor = Rent::get()?.minimum_balance(escrow_info.data_len());
+            require!(
+                escrow_info.lamports() >= rent_floor,
+                EscrowError::BelowRentExempt
+            );
+        }
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let winner_ta = ctx
+            .accounts
+            .winner_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+        require_undelegated(escrow_ta)?;
+        // The payout must land with the default winner themselves
+        require!(winner_ta.owner == winner, EscrowError::InvalidWinner);
+        require!(winner_ta.mint == token_mint, EscrowError::WrongMint);
+
+        let mut transfers = vec![(winner_ta.to_account_info(), payout)];
+        if treasury_fee > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                treasury_ta.owner == treasury_key,
+                EscrowError::NotAuthorized
+            );
+            require!(treasury_ta.mint == token_mint, EscrowError::WrongMint);
+            transfers.push((treasury_ta.to_account_info(), treasury_fee));
+        }
+        if crank_reward > 0 {
+            let cranker_ta = ctx
+                .accounts
+                .cranker_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(cranker_ta.owner == cranker, EscrowError::NotAuthorized);
+            require!(cranker_ta.mint == token_mint, EscrowError::WrongMint);
+            transfers.push((cranker_ta.to_account_info(), crank_reward));
+        }
+
+        for (to, amount) in transfers {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to,
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        close_escrow_ata_if_empty(
+            escrow_ta,
+            token_prog,
+            ctx.accounts.escrow.to_account_info(),
+            ctx.accounts.rent_payer.to_account_info(),
+            signer_seeds,
+        )?;
+    }
+
+    profile!("crank_settle", "transferred");
+
+    // ---------------------------------------------------------------
+    // Record the outcome (mutable borrow after all CPI), then close
+    // unless the escrow opted to stay readable until `sweep_closed`.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Settled)?;
+    escrow.settling = false;
+    escrow.settled_at = clock.unix_timestamp;
+    escrow.winner = winner;
+    if !retain_record {
+        escrow.close(ctx.accounts.rent_payer.to_account_info())?;
+    }
+
+    emit!(EscrowCranked {
+        lobby_id_hash,
+        cranker,
+        winner,
+        payout,
+        fee,
+        crank_reward,
+    });
+    if !retain_record {
+        emit!(EscrowClosed {
+            lobby_id_hash,
+            winner,
+        });
+    }
+
+    Ok(())
+}
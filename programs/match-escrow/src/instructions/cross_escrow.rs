@@ -0,0 +1,585 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{
+    CONFIG_SEED, CROSS_ESCROW_SEED, FEE_BPS, MAX_FEE_BPS, MAX_WAGER_LAMPORTS, MIN_WAGER_LAMPORTS,
+};
+use crate::error::EscrowError;
+use crate::events::{
+    CrossDepositMade, CrossEscrowForfeited, CrossEscrowInitialized, CrossEscrowSettled,
+};
+use crate::state::{CrossAssetEscrow, ProgramConfig};
+
+/// Arguments for `initialize_cross_escrow`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeCrossEscrowParams {
+    /// SHA-256 hash of the Convex lobby ID string (PDA seed)
+    pub lobby_id_hash: [u8; 32],
+    pub host: Pubkey,
+    pub opponent: Pubkey,
+    /// Pubkey::default() = native SOL, otherwise SPL mint
+    pub host_mint: Pubkey,
+    /// Pubkey::default() = native SOL, otherwise SPL mint. Must differ
+    /// from `host_mint`; same-asset matches use `initialize_escrow`.
+    pub opponent_mint: Pubkey,
+    /// Host stake in base units of `host_mint`
+    pub host_wager: u64,
+    /// Opponent stake in base units of `opponent_mint`. Pricing the two
+    /// stakes as equivalent is the caller's job; nothing is checked on-chain.
+    pub opponent_wager: u64,
+    pub treasury: Pubkey,
+    pub deposit_deadline: i64,
+    /// Defaults to `FEE_BPS` when omitted
+    pub fee_bps: Option<u16>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: InitializeCrossEscrowParams)]
+pub struct InitializeCrossEscrow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CrossAssetEscrow::INIT_SPACE,
+        seeds = [CROSS_ESCROW_SEED, params.lobby_id_hash.as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, CrossAssetEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves the depositor's stake, in their own mint, into a cross-asset
+/// escrow.
+#[derive(Accounts)]
+pub struct DepositCross<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CROSS_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, CrossAssetEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Depositor's token account (only needed for an SPL stake).
+    #[account(mut)]
+    pub depositor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account for the depositor's mint, only needed for an
+    /// SPL stake. Must be the escrow PDA's associated token account.
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Depositor's stake mint, validated in handler. Only needed for an SPL stake.
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program owning `mint`. Only needed for an SPL stake.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only payout of both stakes to the winner. Used by
+/// `settle_cross` and `forfeit_cross`; closes the escrow to the authority.
+///
+/// Fees are taken per asset: each stake pays `fee_bps` of itself, rounded
+/// down, in its own mint, so the treasury needs an account for each SPL
+/// mint that carries a non-zero fee. The winner receives the remainder of
+/// both stakes in their original tokens. Each side's accounts are only
+/// needed when that side's stake is an SPL token; a native SOL stake is
+/// paid from the escrow's lamports.
+#[derive(Accounts)]
+pub struct SettleCross<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CROSS_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, CrossAssetEscrow>,
+
+    /// CHECK: Validated against escrow.host or escrow.opponent in handler.
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against escrow.treasury in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Mint of the host's stake.
+    #[account(address = escrow.host_mint @ EscrowError::WrongMint)]
+    pub host_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program owning `host_mint`.
+    pub host_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Escrow's account holding the host's stake.
+    #[account(mut)]
+    pub escrow_host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Winner's account for `host_mint`.
+    #[account(mut)]
+    pub winner_host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's account for `host_mint` (only needed with a fee).
+    #[account(mut)]
+    pub treasury_host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Mint of the opponent's stake.
+    #[account(address = escrow.opponent_mint @ EscrowError::WrongMint)]
+    pub opponent_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program owning `opponent_mint`.
+    pub opponent_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Escrow's account holding the opponent's stake.
+    #[account(mut)]
+    pub escrow_opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Winner's account for `opponent_mint`.
+    #[account(mut)]
+    pub winner_opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's account for `opponent_mint` (only needed with a fee).
+    #[account(mut)]
+    pub treasury_opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_handler(
+    ctx: Context<InitializeCrossEscrow>,
+    params: InitializeCrossEscrowParams,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    let fee_bps = params.fee_bps.unwrap_or(FEE_BPS);
+    require!(fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+    require!(params.host_mint != params.opponent_mint, EscrowError::SameMint);
+    require!(
+        params.host != Pubkey::default() && params.opponent != Pubkey::default(),
+        EscrowError::DuplicatePlayer
+    );
+    require!(params.host != params.opponent, EscrowError::DuplicatePlayer);
+
+    for (mint, wager) in [
+        (params.host_mint, params.host_wager),
+        (params.opponent_mint, params.opponent_wager),
+    ] {
+        require!(wager > 0, EscrowError::ZeroWager);
+        if mint == Pubkey::default() {
+            require!(wager >= MIN_WAGER_LAMPORTS, EscrowError::WagerTooSmall);
+            require!(wager <= MAX_WAGER_LAMPORTS, EscrowError::WagerTooLarge);
+        }
+    }
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.lobby_id_hash = params.lobby_id_hash;
+    escrow.host = params.host;
+    escrow.opponent = params.opponent;
+    escrow.host_mint = params.host_mint;
+    escrow.opponent_mint = params.opponent_mint;
+    escrow.host_wager = params.host_wager;
+    escrow.opponent_wager = params.opponent_wager;
+    escrow.host_deposited = false;
+    escrow.opponent_deposited = false;
+    escrow.treasury = params.treasury;
+    escrow.authority = ctx.accounts.authority.key();
+    escrow.settled = false;
+    escrow.bump = ctx.bumps.escrow;
+    escrow.deposit_deadline = params.deposit_deadline;
+    escrow.fee_bps = fee_bps;
+
+    emit!(CrossEscrowInitialized {
+        lobby_id_hash: params.lobby_id_hash,
+        host: params.host,
+        opponent: params.opponent,
+        host_mint: params.host_mint,
+        opponent_mint: params.opponent_mint,
+        host_wager: params.host_wager,
+        opponent_wager: params.opponent_wager,
+        treasury: params.treasury,
+        authority: escrow.authority,
+        deposit_deadline: params.deposit_deadline,
+        fee_bps,
+    });
+
+    Ok(())
+}
+
+pub fn deposit_handler(ctx: Context<DepositCross>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    // ---------------------------------------------------------------
+    // Extract all needed values BEFORE any CPI calls (avoids E0502).
+    // ---------------------------------------------------------------
+    let depositor_key = ctx.accounts.depositor.key();
+    let escrow = &ctx.accounts.escrow;
+    let is_host = depositor_key == escrow.host;
+    let is_opponent = depositor_key == escrow.opponent;
+    let (already_deposited, stake_mint, amount) = if is_host {
+        (escrow.host_deposited, escrow.host_mint, escrow.host_wager)
+    } else {
+        (escrow.opponent_deposited, escrow.opponent_mint, escrow.opponent_wager)
+    };
+    let settled = escrow.settled;
+    let escrow_key = escrow.key();
+    let deposit_deadline = escrow.deposit_deadline;
+    let lobby_id_hash = escrow.lobby_id_hash;
+
+    require!(is_host || is_opponent, EscrowError::NotAuthorized);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        Clock::get()?.unix_timestamp <= deposit_deadline,
+        EscrowError::DepositWindowClosed
+    );
+    require!(!already_deposited, EscrowError::AlreadyDeposited);
+
+    // ---------------------------------------------------------------
+    // Transfer the stake into the escrow
+    // ---------------------------------------------------------------
+    if stake_mint == Pubkey::default() {
+        let balance_before = ctx.accounts.escrow.to_account_info().lamports();
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let received = ctx
+            .accounts
+            .escrow
+            .to_account_info()
+            .lamports()
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        require!(received == amount, EscrowError::DepositShortfall);
+    } else {
+        let depositor_ta = ctx
+            .accounts
+            .depositor_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(mint.key() == stake_mint, EscrowError::WrongMint);
+        require!(
+            depositor_ta.owner == depositor_key,
+            EscrowError::WrongTokenOwner
+        );
+        require!(depositor_ta.mint == stake_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &escrow_key,
+                    &stake_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+
+        let balance_before = escrow_ta.amount;
+
+        let cpi_ctx = CpiContext::new(
+            token_prog.to_account_info(),
+            TransferChecked {
+                from: depositor_ta.to_account_info(),
+                mint: mint.to_account_info(),
+                to: escrow_ta.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        escrow_ta.reload()?;
+        let received = escrow_ta
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        require!(received == amount, EscrowError::DepositShortfall);
+    }
+
+    // ---------------------------------------------------------------
+    // Mark deposit flag (mutable borrow begins here, after all CPI)
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    if is_host {
+        escrow.host_deposited = true;
+    } else {
+        escrow.opponent_deposited = true;
+    }
+
+    emit!(CrossDepositMade {
+        lobby_id_hash,
+        depositor: depositor_key,
+        mint: stake_mint,
+        amount,
+    });
+
+    Ok(())
+}
+
+pub fn settle_handler(ctx: Context<SettleCross>, winner: Pubkey) -> Result<()> {
+    let [(host_asset_fee, host_asset_payout), (opponent_asset_fee, opponent_asset_payout)] =
+        pay_cross_winner(ctx.accounts, winner)?;
+
+    emit!(CrossEscrowSettled {
+        lobby_id_hash: ctx.accounts.escrow.lobby_id_hash,
+        winner,
+        host_asset_payout,
+        host_asset_fee,
+        opponent_asset_payout,
+        opponent_asset_fee,
+    });
+
+    Ok(())
+}
+
+pub fn forfeit_handler(ctx: Context<SettleCross>, forfeiter: Pubkey) -> Result<()> {
+    // The winner is the other player; outsiders cannot forfeit
+    let winner = ctx
+        .accounts
+        .escrow
+        .other_player(&forfeiter)
+        .ok_or(EscrowError::InvalidForfeiter)?;
+
+    let [(host_asset_fee, host_asset_payout), (opponent_asset_fee, opponent_asset_payout)] =
+        pay_cross_winner(ctx.accounts, winner)?;
+
+    emit!(CrossEscrowForfeited {
+        lobby_id_hash: ctx.accounts.escrow.lobby_id_hash,
+        forfeiter,
+        winner,
+        host_asset_payout,
+        host_asset_fee,
+        opponent_asset_payout,
+        opponent_asset_fee,
+    });
+
+    Ok(())
+}
+
+/// Accounts for releasing one side's stake. All `None` for native SOL.
+struct StakeAccounts<'a, 'info> {
+    mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    token_program: Option<&'a Interface<'info, TokenInterface>>,
+    escrow_ta: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    winner_ta: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    treasury_ta: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Validates `winner` and releases both stakes, each split per
+/// `CrossAssetEscrow::split_stake`. Returns `(fee, payout)` for the host's
+/// asset and then the opponent's.
+fn pay_cross_winner(accounts: &mut SettleCross, winner: Pubkey) -> Result<[(u64, u64); 2]> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let escrow = &accounts.escrow;
+    let winner_is_player = escrow.other_player(&winner).is_some();
+    let both_funded = escrow.both_funded();
+    let settled = escrow.settled;
+    let treasury_key = escrow.treasury;
+    let host_side = (escrow.host_mint, escrow.split_stake(escrow.host_wager)?);
+    let opponent_side = (escrow.opponent_mint, escrow.split_stake(escrow.opponent_wager)?);
+    let lobby_id_hash = escrow.lobby_id_hash;
+    let bump = escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(winner_is_player, EscrowError::InvalidWinner);
+    require!(both_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        accounts.winner.key() == winner,
+        EscrowError::InvalidWinner
+    );
+    require!(
+        accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[CROSS_ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Release each stake in its own asset
+    // ---------------------------------------------------------------
+    let escrow_info = accounts.escrow.to_account_info();
+    let winner_info = accounts.winner.to_account_info();
+    let treasury_info = accounts.treasury.to_account_info();
+    let sides = [
+        (
+            host_side,
+            StakeAccounts {
+                mint: accounts.host_mint.as_ref(),
+                token_program: accounts.host_token_program.as_ref(),
+                escrow_ta: accounts.escrow_host_token_account.as_ref(),
+                winner_ta: accounts.winner_host_token_account.as_ref(),
+                treasury_ta: accounts.treasury_host_token_account.as_ref(),
+            },
+        ),
+        (
+            opponent_side,
+            StakeAccounts {
+                mint: accounts.opponent_mint.as_ref(),
+                token_program: accounts.opponent_token_program.as_ref(),
+                escrow_ta: accounts.escrow_opponent_token_account.as_ref(),
+                winner_ta: accounts.winner_opponent_token_account.as_ref(),
+                treasury_ta: accounts.treasury_opponent_token_account.as_ref(),
+            },
+        ),
+    ];
+    for ((stake_mint, split), stake) in sides {
+        release_stake(
+            &escrow_info,
+            &winner_info,
+            &treasury_info,
+            stake_mint,
+            split,
+            stake,
+            signer_seeds,
+        )?;
+    }
+
+    // ---------------------------------------------------------------
+    // Mark settled (mutable borrow after all CPI).
+    // The `close = authority` constraint reclaims rent after handler.
+    // ---------------------------------------------------------------
+    accounts.escrow.settled = true;
+
+    Ok([host_side.1, opponent_side.1])
+}
+
+/// Pays one stake's `(fee, payout)` split to the treasury and the winner,
+/// from the escrow's lamports for native SOL or its ATA for an SPL mint.
+fn release_stake<'info>(
+    escrow_info: &AccountInfo<'info>,
+    winner_info: &AccountInfo<'info>,
+    treasury_info: &AccountInfo<'info>,
+    stake_mint: Pubkey,
+    (fee, payout): (u64, u64),
+    stake: StakeAccounts<'_, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let total = payout
+        .checked_add(fee)
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    if stake_mint == Pubkey::default() {
+        // Native SOL: direct lamport manipulation (PDA owns the lamports).
+        require!(
+            escrow_info.lamports() >= total,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= payout;
+        **winner_info.try_borrow_mut_lamports()? += payout;
+
+        **escrow_info.try_borrow_mut_lamports()? -= fee;
+        **treasury_info.try_borrow_mut_lamports()? += fee;
+        return Ok(());
+    }
+
+    let escrow_ta = stake.escrow_ta.ok_or(EscrowError::MissingSplAccount)?;
+    let winner_ta = stake.winner_ta.ok_or(EscrowError::MissingSplAccount)?;
+    let token_prog = stake.token_program.ok_or(EscrowError::MissingSplAccount)?;
+    let mint = stake.mint.ok_or(EscrowError::MissingSplAccount)?;
+
+    require!(
+        escrow_ta.key()
+            == get_associated_token_address_with_program_id(
+                &escrow_info.key(),
+                &stake_mint,
+                &token_prog.key(),
+            ),
+        EscrowError::InvalidEscrowTokenAccount
+    );
+    require!(escrow_ta.amount >= total, EscrowError::InsufficientFunds);
+    require!(
+        winner_ta.owner == winner_info.key(),
+        EscrowError::WrongTokenOwner
+    );
+    require!(winner_ta.mint == stake_mint, EscrowError::WrongMint);
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_prog.to_account_info(),
+            TransferChecked {
+                from: escrow_ta.to_account_info(),
+                mint: mint.to_account_info(),
+                to: winner_ta.to_account_info(),
+                authority: escrow_info.clone(),
+            },
+            signer_seeds,
+        ),
+        payout,
+        mint.decimals,
+    )?;
+
+    // Skipped for zero-rake escrows, as in `settle`
+    if fee > 0 {
+        let treasury_ta = stake.treasury_ta.ok_or(EscrowError::MissingSplAccount)?;
+        require!(
+            treasury_ta.owner == treasury_info.key(),
+            EscrowError::NotAuthorized
+        );
+        require!(treasury_ta.mint == stake_mint, EscrowError::WrongMint);
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_prog.to_account_info(),
+                TransferChecked {
+                    from: escrow_ta.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: treasury_ta.to_account_info(),
+                    authority: escrow_info.clone(),
+                },
+                signer_seeds,
+            ),
+            fee,
+            mint.decimals,
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,11 +1,36 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token;
-use anchor_spl::token::{TokenAccount, Transfer as SplTransfer};
-use crate::constants::ESCROW_SEED;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED, PLAYER_INDEX_SEED, TOKEN_METADATA_PROGRAM_ID};
 use crate::error::EscrowError;
-use crate::state::MatchEscrow;
+use crate::events::DepositMade;
+use crate::instructions::player_index::index_add;
+use crate::state::{DepositMethod, EscrowStatus, MatchEscrow, PlayerIndex, ProgramConfig};
 
+/// Moves the depositor's wager into the escrow.
+///
+/// For gasless SPL deposits a relayer signs as `depositor` and passes a
+/// `delegate` the player approved on their token account. The deposit is
+/// then credited to the token account's owner rather than the signer.
+///
+/// Token-2022 mints with the transfer-fee extension are accounted by
+/// balance: the player is credited with what actually lands in the escrow
+/// (wager minus the epoch's transfer fee), and every payout is computed
+/// from those credited amounts. Payouts are themselves fee-bearing
+/// transfers, so recipients get slightly less than the computed share.
+/// Any other shortfall (e.g. a mint that skims through a hook) is rejected
+/// with `DepositShortfall`.
+///
+/// Escrows with a `required_collection` only accept deposits from a player
+/// whose `entry_nft_token_account` holds an NFT whose Metaplex metadata
+/// names that collection as verified.
+///
+/// `deposit_installment` takes the same accounts and pays part of the wager.
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
@@ -18,55 +43,174 @@ pub struct Deposit<'info> {
     )]
     pub escrow: Account<'info, MatchEscrow>,
 
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Escrow authority co-signature; only needed when the escrow was
+    /// created with `require_authorized_deposit`.
+    pub authority: Option<Signer<'info>>,
+
+    /// SPL delegate approved by the player for at least their wager. Only
+    /// for relayed SPL deposits; native SOL can't be delegated.
+    pub delegate: Option<Signer<'info>>,
+
     /// Depositor's token account (only needed for SPL deposits).
     /// CHECK: Validated in handler; optional for native SOL path.
     #[account(mut)]
-    pub depositor_token_account: Option<Account<'info, TokenAccount>>,
+    pub depositor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Escrow's token account (ATA owned by PDA, only needed for SPL deposits).
+    /// Escrow's token account, only needed for SPL deposits. Must be the
+    /// associated token account of the escrow PDA for `token_mint` under
+    /// `token_program` (Token or Token-2022).
     /// CHECK: Validated in handler; optional for native SOL path.
     #[account(mut)]
-    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL deposits.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL deposits.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
-    /// CHECK: Token program, validated by address constraint. Only needed for SPL deposits.
-    #[account(address = anchor_spl::token::ID)]
-    pub token_program: Option<UncheckedAccount<'info>>,
+    /// Player's token account holding the entry NFT. Only needed for
+    /// NFT-gated escrows.
+    pub entry_nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata PDA of the entry NFT's mint; validated and
+    /// parsed in `verify_entry_nft`.
+    pub entry_nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// Credited player's `PlayerIndex` page; when passed, the lobby is
+    /// listed in it.
+    #[account(
+        mut,
+        seeds = [PLAYER_INDEX_SEED, player_index.player.as_ref(), &[player_index.page]],
+        bump = player_index.bump,
+    )]
+    pub player_index: Option<Box<Account<'info, PlayerIndex>>>,
 
     pub system_program: Program<'info, System>,
 }
 
+/// The player a deposit is credited to. A relayed deposit is credited to
+/// the owner of the source token account rather than the signer.
+pub(crate) fn credited_player(accounts: &Deposit) -> Result<Pubkey> {
+    match (&accounts.delegate, &accounts.depositor_token_account) {
+        (Some(_), Some(depositor_ta)) => Ok(depositor_ta.owner),
+        (Some(_), None) => err!(EscrowError::MissingSplAccount),
+        (None, _) => Ok(accounts.depositor.key()),
+    }
+}
+
 pub fn handler(ctx: Context<Deposit>) -> Result<()> {
+    pay_toward_wager(ctx, None)
+}
+
+/// Pays `amount` toward the depositor's wager. The seat only counts as
+/// deposited once the running total reaches the wager, and an installment
+/// may not overshoot it. A later plain `deposit` pays whatever is left.
+/// Mints with a transfer fee are rejected, since a fee on every installment
+/// would keep the total short of the wager.
+pub fn installment_handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    pay_toward_wager(ctx, Some(amount))
+}
+
+fn pay_toward_wager(ctx: Context<Deposit>, installment: Option<u64>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
     // ---------------------------------------------------------------
     // Extract all needed values BEFORE any CPI calls.
     // This avoids E0502: cannot borrow `ctx.accounts.escrow` as mutable
     // because it is also borrowed as immutable.
     // ---------------------------------------------------------------
-    let depositor_key = ctx.accounts.depositor.key();
+    let depositor_key = credited_player(ctx.accounts)?;
     let host = ctx.accounts.escrow.host;
-    let opponent = ctx.accounts.escrow.opponent;
+    let is_player = ctx.accounts.escrow.is_player(&depositor_key);
     let host_deposited = ctx.accounts.escrow.host_deposited;
     let opponent_deposited = ctx.accounts.escrow.opponent_deposited;
     let settled = ctx.accounts.escrow.settled;
-    let amount = ctx.accounts.escrow.wager_lamports;
     let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let escrow_key = ctx.accounts.escrow.key();
+    let deposit_window_open = ctx.accounts.escrow.deposit_window_open(&Clock::get()?);
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
 
     // Determine role
     let is_host = depositor_key == host;
-    let is_opponent = depositor_key == opponent;
 
-    require!(is_host || is_opponent, EscrowError::NotAuthorized);
+    require!(is_player, EscrowError::NotAuthorized);
     require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        !ctx.accounts.escrow.sponsored,
+        EscrowError::SponsoredEscrow
+    );
+    if ctx.accounts.escrow.require_authorized_deposit {
+        let authority = ctx
+            .accounts
+            .authority
+            .as_ref()
+            .ok_or(EscrowError::NotAuthorized)?;
+        require!(
+            authority.key() == ctx.accounts.escrow.authority,
+            EscrowError::NotAuthorized
+        );
+    }
+    require!(deposit_window_open, EscrowError::DepositWindowClosed);
+
+    let required_collection = ctx.accounts.escrow.required_collection;
+    if required_collection != Pubkey::default() {
+        verify_entry_nft(
+            ctx.accounts.entry_nft_token_account.as_deref(),
+            ctx.accounts.entry_nft_metadata.as_ref(),
+            &depositor_key,
+            &required_collection,
+        )?;
+    }
 
     if is_host {
         require!(!host_deposited, EscrowError::AlreadyDeposited);
     } else {
         require!(!opponent_deposited, EscrowError::AlreadyDeposited);
     }
+    ctx.accounts
+        .escrow
+        .set_deposit_method(is_host, DepositMethod::OnChain)?;
+
+    // Each side stakes its own amount (uneven wagers for handicap matches)
+    let (wager, already_paid) = if is_host {
+        (
+            ctx.accounts.escrow.host_wager,
+            ctx.accounts.escrow.host_deposited_amount,
+        )
+    } else {
+        (
+            ctx.accounts.escrow.opponent_wager,
+            ctx.accounts.escrow.opponent_deposited_amount,
+        )
+    };
+    let remaining = wager
+        .checked_sub(already_paid)
+        .ok_or(EscrowError::ExceedsWager)?;
+    let amount = match installment {
+        Some(amount) => {
+            require!(amount > 0, EscrowError::ZeroWager);
+            require!(amount <= remaining, EscrowError::ExceedsWager);
+            amount
+        }
+        None => remaining,
+    };
+
+    profile!("deposit", "validated");
 
     // ---------------------------------------------------------------
     // Transfer funds into the escrow
     // ---------------------------------------------------------------
-    if is_native {
+    let received = if is_native {
+        require!(ctx.accounts.delegate.is_none(), EscrowError::NotAuthorized);
+
+        let balance_before = ctx.accounts.escrow.to_account_info().lamports();
+
         let cpi_ctx = CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
@@ -75,6 +219,16 @@ pub fn handler(ctx: Context<Deposit>) -> Result<()> {
             },
         );
         system_program::transfer(cpi_ctx, amount)?;
+
+        let received = ctx
+            .accounts
+            .escrow
+            .to_account_info()
+            .lamports()
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        require!(received == amount, EscrowError::DepositShortfall);
+        received
     } else {
         let depositor_ta = ctx
             .accounts
@@ -91,27 +245,264 @@ pub fn handler(ctx: Context<Deposit>) -> Result<()> {
             .token_program
             .as_ref()
             .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        // Funds must come from the credited player's own account; for
+        // relayed deposits `depositor_key` is already that owner.
+        require!(
+            depositor_ta.owner == depositor_key,
+            EscrowError::WrongTokenOwner
+        );
+
+        // Both sides of the transfer must be the escrow's mint, and the
+        // destination must be the escrow PDA's canonical ATA.
+        require!(
+            depositor_ta.mint == token_mint,
+            EscrowError::WrongMint
+        );
+        require!(escrow_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.owner == escrow_key,
+            EscrowError::InvalidEscrowTokenAccount
+        );
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &escrow_key,
+                    &token_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+        require_undelegated(escrow_ta)?;
+
+        // Relayed deposits move funds under the player's SPL approval
+        let transfer_authority = match &ctx.accounts.delegate {
+            Some(delegate) => {
+                require!(
+                    depositor_ta.delegate == Some(delegate.key()).into(),
+                    EscrowError::NotAuthorized
+                );
+                require!(
+                    depositor_ta.delegated_amount >= amount,
+                    EscrowError::InsufficientFunds
+                );
+                delegate.to_account_info()
+            }
+            None => ctx.accounts.depositor.to_account_info(),
+        };
+
+        let expected = net_of_transfer_fee(mint, amount)?;
+        require!(
+            installment.is_none() || expected == amount,
+            EscrowError::InstallmentsUnsupported
+        );
+        let balance_before = escrow_ta.amount;
 
         let cpi_ctx = CpiContext::new(
             token_prog.to_account_info(),
-            SplTransfer {
+            TransferChecked {
                 from: depositor_ta.to_account_info(),
+                mint: mint.to_account_info(),
                 to: escrow_ta.to_account_info(),
-                authority: ctx.accounts.depositor.to_account_info(),
+                authority: transfer_authority,
             },
         );
-        token::transfer(cpi_ctx, amount)?;
-    }
+        token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+
+        // Re-read the escrow balance to catch mints that skim on transfer.
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        escrow_ta.reload()?;
+        let received = escrow_ta
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        require!(received == expected, EscrowError::DepositShortfall);
+        received
+    };
 
     // ---------------------------------------------------------------
-    // Mark deposit flag (mutable borrow begins here, after all CPI)
+    // Record the deposit (mutable borrow begins here, after all CPI)
     // ---------------------------------------------------------------
+    let paid = already_paid
+        .checked_add(received)
+        .ok_or(EscrowError::InsufficientFunds)?;
+    // A plain deposit always completes the seat, even when a transfer fee
+    // left it short of the wager
+    let complete = installment.is_none() || paid >= wager;
     let escrow = &mut ctx.accounts.escrow;
     if is_host {
-        escrow.host_deposited = true;
+        escrow.host_deposited = complete;
+        escrow.host_deposited_amount = paid;
     } else {
-        escrow.opponent_deposited = true;
+        escrow.opponent_deposited = complete;
+        escrow.opponent_deposited_amount = paid;
+    }
+    // An unfinished installment leaves the lifecycle stage where it was
+    if complete {
+        if escrow.first_depositor == Pubkey::default() {
+            escrow.first_depositor = depositor_key;
+        }
+        let funding = escrow.funding_status();
+        if funding == EscrowStatus::Funded {
+            escrow.funded_at = Clock::get()?.unix_timestamp;
+        }
+        escrow.transition(funding)?;
     }
 
+    emit!(DepositMade {
+        lobby_id_hash,
+        depositor: depositor_key,
+        amount,
+    });
+
+    index_add(
+        ctx.accounts.player_index.as_deref_mut(),
+        depositor_key,
+        lobby_id_hash,
+    )?;
+
+    Ok(())
+}
+
+/// What the escrow receives when `amount` is sent through `mint`. Token-2022
+/// withholds the current epoch's transfer fee from the destination; every
+/// other mint delivers in full.
+pub(crate) fn net_of_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(amount);
+    }
+
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let fee = match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(EscrowError::DepositShortfall)?,
+        Err(_) => 0,
+    };
+    Ok(amount
+        .checked_sub(fee)
+        .ok_or(EscrowError::DepositShortfall)?)
+}
+
+/// Rejects an escrow token account with a delegate, who could move custody
+/// funds without the PDA's signature. Only the PDA could approve one, and
+/// SPL Token clears the delegate when ownership changes, so this guards an
+/// assumption rather than a known path.
+pub(crate) fn require_undelegated(escrow_ta: &spl_token_2022::state::Account) -> Result<()> {
+    require!(escrow_ta.delegate.is_none(), EscrowError::EscrowDelegated);
+    Ok(())
+}
+
+/// Checks that `owner` holds one unit of an NFT whose metadata lists
+/// `collection` as its verified collection.
+fn verify_entry_nft(
+    nft_ta: Option<&TokenAccount>,
+    metadata: Option<&UncheckedAccount>,
+    owner: &Pubkey,
+    collection: &Pubkey,
+) -> Result<()> {
+    let nft_ta = nft_ta.ok_or(EscrowError::MissingEntryNft)?;
+    let metadata = metadata.ok_or(EscrowError::MissingEntryNft)?;
+
+    require!(nft_ta.owner == *owner, EscrowError::MissingEntryNft);
+    require!(nft_ta.amount == 1, EscrowError::MissingEntryNft);
+
+    let (expected, _) = Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            nft_ta.mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    );
+    require!(metadata.key() == expected, EscrowError::MissingEntryNft);
+    require!(
+        *metadata.owner == TOKEN_METADATA_PROGRAM_ID,
+        EscrowError::MissingEntryNft
+    );
+
+    let data = metadata.try_borrow_data()?;
+    let verified = metadata_collection(&data).ok_or(EscrowError::MissingEntryNft)?;
+    require!(verified == *collection, EscrowError::MissingEntryNft);
+
     Ok(())
 }
+
+/// Walks a Metaplex `Metadata` account up to its `collection` field and
+/// returns the collection key if it is verified. Returns `None` for any
+/// other layout.
+fn metadata_collection(data: &[u8]) -> Option<Pubkey> {
+    let mut rest = data;
+    let mut take = |n: usize| -> Option<&[u8]> {
+        let (head, tail) = rest.split_at_checked(n)?;
+        rest = tail;
+        Some(head)
+    };
+
+    // key (MetadataV1 = 4), update_authority, mint
+    if take(1)?[0] != 4 {
+        return None;
+    }
+    take(64)?;
+    // name, symbol, uri: borsh strings
+    for _ in 0..3 {
+        let len = u32::from_le_bytes(take(4)?.try_into().ok()?) as usize;
+        take(len)?;
+    }
+    // seller_fee_basis_points
+    take(2)?;
+    // creators: Option<Vec<Creator>>, 34 bytes per creator
+    if take(1)?[0] == 1 {
+        let count = u32::from_le_bytes(take(4)?.try_into().ok()?) as usize;
+        take(count.checked_mul(34)?)?;
+    }
+    // primary_sale_happened, is_mutable
+    take(2)?;
+    // edition_nonce, token_standard: Option<u8>
+    for _ in 0..2 {
+        if take(1)?[0] == 1 {
+            take(1)?;
+        }
+    }
+    // collection: Option<Collection { verified, key }>
+    if take(1)?[0] != 1 {
+        return None;
+    }
+    let verified = take(1)?[0] == 1;
+    let key = Pubkey::try_from(take(32)?).ok()?;
+    verified.then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_option::COption;
+
+    #[test]
+    fn delegated_escrow_account_is_rejected() {
+        let mut escrow_ta = spl_token_2022::state::Account {
+            delegate: COption::Some(Pubkey::new_unique()),
+            delegated_amount: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            require_undelegated(&escrow_ta).unwrap_err(),
+            EscrowError::EscrowDelegated.into()
+        );
+
+        escrow_ta.delegate = COption::None;
+        escrow_ta.delegated_amount = 0;
+        assert!(require_undelegated(&escrow_ta).is_ok());
+    }
+}
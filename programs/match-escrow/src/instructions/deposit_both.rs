@@ -0,0 +1,267 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED};
+use crate::error::EscrowError;
+use crate::events::DepositMade;
+use crate::instructions::deposit::{net_of_transfer_fee, require_undelegated};
+use crate::state::{DepositMethod, EscrowStatus, MatchEscrow, ProgramConfig};
+
+/// Funds both seats in one transaction for server-orchestrated matches.
+/// Both players sign, since each wager leaves their own wallet; escrows
+/// created with `require_authorized_deposit` also need the authority.
+/// Same checks as `deposit`, applied to each side. NFT-gated escrows are
+/// not supported here; each player proves ownership via `deposit`.
+#[derive(Accounts)]
+pub struct DepositBoth<'info> {
+    #[account(mut)]
+    pub host: Signer<'info>,
+
+    #[account(mut)]
+    pub opponent: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = host @ EscrowError::NotAuthorized,
+        has_one = opponent @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Escrow authority co-signature; only needed when the escrow was
+    /// created with `require_authorized_deposit`.
+    pub authority: Option<Signer<'info>>,
+
+    /// Host's token account (only needed for SPL deposits).
+    #[account(mut)]
+    pub host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Opponent's token account (only needed for SPL deposits).
+    #[account(mut)]
+    pub opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account, only needed for SPL deposits. Must be the
+    /// escrow PDA's associated token account.
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL deposits.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL deposits.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DepositBoth>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    // ---------------------------------------------------------------
+    // Extract all needed values BEFORE any CPI calls.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    let host_wager = ctx.accounts.escrow.host_wager;
+    let opponent_wager = ctx.accounts.escrow.opponent_wager;
+    let settled = ctx.accounts.escrow.settled;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let escrow_key = ctx.accounts.escrow.key();
+    let deposit_window_open = ctx.accounts.escrow.deposit_window_open(&Clock::get()?);
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        !ctx.accounts.escrow.sponsored,
+        EscrowError::SponsoredEscrow
+    );
+    require!(
+        !ctx.accounts.escrow.host_deposited && !ctx.accounts.escrow.opponent_deposited,
+        EscrowError::AlreadyDeposited
+    );
+    // A seat part-way through installments has paid in already
+    require!(
+        ctx.accounts.escrow.total_deposited()? == 0,
+        EscrowError::AlreadyDeposited
+    );
+    if ctx.accounts.escrow.require_authorized_deposit {
+        let authority = ctx
+            .accounts
+            .authority
+            .as_ref()
+            .ok_or(EscrowError::NotAuthorized)?;
+        require!(
+            authority.key() == ctx.accounts.escrow.authority,
+            EscrowError::NotAuthorized
+        );
+    }
+    require!(deposit_window_open, EscrowError::DepositWindowClosed);
+    require!(
+        ctx.accounts.escrow.required_collection == Pubkey::default(),
+        EscrowError::MissingEntryNft
+    );
+
+    // ---------------------------------------------------------------
+    // Transfer both wagers into the escrow
+    // ---------------------------------------------------------------
+    let (received, host_net, opponent_net) = if is_native {
+        let balance_before = ctx.accounts.escrow.to_account_info().lamports();
+
+        for (from, amount) in [
+            (ctx.accounts.host.to_account_info(), host_wager),
+            (ctx.accounts.opponent.to_account_info(), opponent_wager),
+        ] {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from,
+                        to: ctx.accounts.escrow.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let received = ctx
+            .accounts
+            .escrow
+            .to_account_info()
+            .lamports()
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        (received, host_wager, opponent_wager)
+    } else {
+        let host_ta = ctx
+            .accounts
+            .host_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let opponent_ta = ctx
+            .accounts
+            .opponent_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(host_ta.owner == host, EscrowError::WrongTokenOwner);
+        require!(opponent_ta.owner == opponent, EscrowError::WrongTokenOwner);
+        require!(host_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(opponent_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &escrow_key,
+                    &token_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+        require_undelegated(escrow_ta)?;
+
+        // Transfer-fee mints credit each seat with what actually lands
+        let host_net = net_of_transfer_fee(mint, host_wager)?;
+        let opponent_net = net_of_transfer_fee(mint, opponent_wager)?;
+        let balance_before = escrow_ta.amount;
+
+        for (from, authority, amount) in [
+            (
+                host_ta.to_account_info(),
+                ctx.accounts.host.to_account_info(),
+                host_wager,
+            ),
+            (
+                opponent_ta.to_account_info(),
+                ctx.accounts.opponent.to_account_info(),
+                opponent_wager,
+            ),
+        ] {
+            // Free-entry seats may stake nothing
+            if amount == 0 {
+                continue;
+            }
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from,
+                        mint: mint.to_account_info(),
+                        to: escrow_ta.to_account_info(),
+                        authority,
+                    },
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+
+        // Re-read the escrow balance to catch mints that skim on transfer.
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        escrow_ta.reload()?;
+        let received = escrow_ta
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        (received, host_net, opponent_net)
+    };
+    require!(
+        Some(received) == host_net.checked_add(opponent_net),
+        EscrowError::DepositShortfall
+    );
+
+    // ---------------------------------------------------------------
+    // Record both deposits (mutable borrow begins here, after all CPI)
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.host_deposited = true;
+    escrow.opponent_deposited = true;
+    escrow.host_deposited_amount = host_net;
+    escrow.opponent_deposited_amount = opponent_net;
+    escrow.set_deposit_method(true, DepositMethod::OnChain)?;
+    escrow.set_deposit_method(false, DepositMethod::OnChain)?;
+    // Both stakes land in one instruction; the host counts as first
+    escrow.first_depositor = escrow.host;
+    escrow.funded_at = Clock::get()?.unix_timestamp;
+    escrow.transition(EscrowStatus::Funded)?;
+
+    emit!(DepositMade {
+        lobby_id_hash,
+        depositor: host,
+        amount: host_wager,
+    });
+    emit!(DepositMade {
+        lobby_id_hash,
+        depositor: opponent,
+        amount: opponent_wager,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::{DisputeRaised, DisputeResolved};
+use crate::state::{EscrowStatus, MatchEscrow};
+
+/// Freezes payouts while a result is contested. Callable by either player
+/// or the authority.
+#[derive(Accounts)]
+pub struct RaiseDispute<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+/// Authority-only unfreeze. To settle in the same step, bundle this with
+/// `settle` or `forfeit` in one transaction; both run atomically. A
+/// contested `settle_with_challenge` proposal is discarded.
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn raise_handler(ctx: Context<RaiseDispute>) -> Result<()> {
+    let caller = ctx.accounts.caller.key();
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(
+        caller == escrow.host || caller == escrow.opponent || caller == escrow.authority,
+        EscrowError::NotAuthorized
+    );
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(!escrow.disputed, EscrowError::Disputed);
+
+    escrow.transition(EscrowStatus::Disputed)?;
+    escrow.disputed = true;
+
+    emit!(DisputeRaised {
+        lobby_id_hash: escrow.lobby_id_hash,
+        raised_by: caller,
+    });
+
+    Ok(())
+}
+
+pub fn resolve_handler(ctx: Context<ResolveDispute>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(escrow.disputed, EscrowError::NotDisputed);
+
+    escrow.transition(EscrowStatus::Funded)?;
+    escrow.disputed = false;
+    escrow.proposed_winner = Pubkey::default();
+    escrow.challenge_deadline = 0;
+
+    emit!(DisputeResolved {
+        lobby_id_hash: escrow.lobby_id_hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::EscrowExpired;
+use crate::state::{EscrowStatus, MatchEscrow};
+
+/// Permissionless last resort once an escrow outlives `expires_at`. Returns
+/// whatever each player deposited plus their share of any bonus, with no
+/// fee, and closes the escrow to its rent payer. Unlike `auto_refund` it
+/// ignores disputes and works for a half-funded escrow, so player funds can
+/// never be locked by an absent operator. Sponsored escrows are rejected.
+#[derive(Accounts)]
+pub struct ExpireRefund<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = host @ EscrowError::NotAuthorized,
+        has_one = opponent @ EscrowError::NotAuthorized,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
+        close = rent_payer,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; receives the host's deposit.
+    #[account(mut)]
+    pub host: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; receives the opponent's deposit.
+    #[account(mut)]
+    pub opponent: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// Host's token account (only needed for SPL refunds of a host deposit).
+    #[account(mut)]
+    pub host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Opponent's token account (only needed for SPL refunds of an opponent deposit).
+    #[account(mut)]
+    pub opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL refunds.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL refunds.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ExpireRefund>) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    let settled = ctx.accounts.escrow.settled;
+    let sponsored = ctx.accounts.escrow.sponsored;
+    let (host_refund, opponent_refund) = ctx.accounts.escrow.refund_amounts()?;
+    let total_refund = ctx.accounts.escrow.total_pot()?;
+    let expired = ctx.accounts.escrow.expired(&Clock::get()?);
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(!settled, EscrowError::AlreadySettled);
+    // A sponsored stake is the authority's, not the players'
+    require!(!sponsored, EscrowError::SponsoredEscrow);
+    require!(expired, EscrowError::NotYetExpired);
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Return each deposit in full
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports are reclaimed by `close = rent_payer`.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let host_info = ctx.accounts.host.to_account_info();
+        let opponent_info = ctx.accounts.opponent.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_refund,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= host_refund;
+        **host_info.try_borrow_mut_lamports()? += host_refund;
+
+        **escrow_info.try_borrow_mut_lamports()? -= opponent_refund;
+        **opponent_info.try_borrow_mut_lamports()? += opponent_refund;
+    } else if total_refund > 0 {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            escrow_ta.amount >= total_refund,
+            EscrowError::InsufficientFunds
+        );
+
+        for (player, player_ta, amount) in [
+            (host, &ctx.accounts.host_token_account, host_refund),
+            (opponent, &ctx.accounts.opponent_token_account, opponent_refund),
+        ] {
+            // Only seats that actually deposited need a token account
+            if amount == 0 {
+                continue;
+            }
+            let player_ta = player_ta.as_ref().ok_or(EscrowError::MissingSplAccount)?;
+            require!(player_ta.owner == player, EscrowError::NotAuthorized);
+            require!(player_ta.mint == token_mint, EscrowError::WrongMint);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: player_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Mark settled (mutable borrow after all CPI).
+    // The `close = rent_payer` constraint reclaims rent after handler.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Refunded)?;
+
+    emit!(EscrowExpired {
+        lobby_id_hash,
+        host_refund,
+        opponent_refund,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::FEE_VAULT_SEED;
+use crate::error::EscrowError;
+use crate::events::{FeesAccrued, FeesSwept};
+use crate::state::FeeVault;
+
+/// Creates the `FeeVault` for a treasury. Permissionless: whatever the vault
+/// holds can only ever be swept to that treasury, so anyone may pay its rent.
+#[derive(Accounts)]
+pub struct InitializeFeeVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any fee destination may have a vault; only its key is stored.
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + FeeVault::INIT_SPACE,
+        seeds = [FEE_VAULT_SEED, treasury.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_handler(ctx: Context<InitializeFeeVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.fee_vault;
+    vault.treasury = ctx.accounts.treasury.key();
+    vault.bump = ctx.bumps.fee_vault;
+    Ok(())
+}
+
+/// Moves everything a vault has accrued to its treasury in one transfer.
+/// Permissionless, since the destination is pinned by the vault. Without
+/// the token accounts it sweeps the vault's lamports above rent; with them,
+/// the whole balance of the vault's token account for `mint`.
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [FEE_VAULT_SEED, fee_vault.treasury.as_ref()],
+        bump = fee_vault.bump,
+        has_one = treasury @ EscrowError::NotAuthorized,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// CHECK: Validated by has_one; receives native sweeps.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Vault's token account holding accrued fees (only needed for SPL sweeps).
+    #[account(mut)]
+    pub vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account receiving the sweep (only needed for SPL sweeps).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Mint of the swept fees, required by `transfer_checked`. Only needed for SPL sweeps.
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL sweeps.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn sweep_handler(ctx: Context<SweepFees>) -> Result<()> {
+    let treasury = ctx.accounts.fee_vault.treasury;
+    let bump = ctx.accounts.fee_vault.bump;
+    let vault_key = ctx.accounts.fee_vault.key();
+
+    let (mint_key, amount) = match ctx.accounts.vault_token_account.as_ref() {
+        None => {
+            // Native fees: everything above the vault's own rent floor
+            let vault_info = ctx.accounts.fee_vault.to_account_info();
+            let treasury_info = ctx.accounts.treasury.to_account_info();
+            let rent_floor = Rent::get()?.minimum_balance(vault_info.data_len());
+            let amount = vault_info.lamports().saturating_sub(rent_floor);
+            require!(amount > 0, EscrowError::NoFeesToSweep);
+
+            **vault_info.try_borrow_mut_lamports()? -= amount;
+            **treasury_info.try_borrow_mut_lamports()? += amount;
+            (Pubkey::default(), amount)
+        }
+        Some(vault_ta) => {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            let token_prog = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            let mint = ctx
+                .accounts
+                .mint
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+
+            require!(vault_ta.owner == vault_key, EscrowError::InvalidFeeVault);
+            require!(treasury_ta.owner == treasury, EscrowError::NotAuthorized);
+            require!(vault_ta.mint == mint.key(), EscrowError::WrongMint);
+            require!(treasury_ta.mint == mint.key(), EscrowError::WrongMint);
+
+            let amount = vault_ta.amount;
+            require!(amount > 0, EscrowError::NoFeesToSweep);
+
+            // PDA signer seeds for CPI
+            let signer_seeds: &[&[&[u8]]] = &[&[FEE_VAULT_SEED, treasury.as_ref(), &[bump]]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: vault_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: treasury_ta.to_account_info(),
+                        authority: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+            (mint.key(), amount)
+        }
+    };
+
+    emit!(FeesSwept {
+        treasury,
+        mint: mint_key,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Where a payout's treasury fee lands: the treasury itself, or its fee
+/// vault when the settler passed one. SPL fees must go to a token account
+/// owned by the returned key.
+pub(crate) fn fee_recipient(
+    vault: Option<&Account<FeeVault>>,
+    treasury: Pubkey,
+) -> Result<Pubkey> {
+    match vault {
+        Some(vault) => {
+            require!(vault.treasury == treasury, EscrowError::InvalidFeeVault);
+            Ok(vault.key())
+        }
+        None => Ok(treasury),
+    }
+}
+
+/// Emits `FeesAccrued` when a payout's fee went to a vault rather than
+/// straight to the treasury.
+pub(crate) fn emit_accrual(
+    vault: Option<&Account<FeeVault>>,
+    lobby_id_hash: [u8; 32],
+    mint: Pubkey,
+    amount: u64,
+) {
+    if let Some(vault) = vault {
+        if amount > 0 {
+            emit!(FeesAccrued {
+                treasury: vault.treasury,
+                lobby_id_hash,
+                mint,
+                amount,
+            });
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::state::MatchEscrow;
+
+/// Escrow status returned by `get_state` as Solana return data.
+///
+/// Borsh-encoded, 140 bytes:
+///
+/// | offset | size | field                |
+/// |--------|------|----------------------|
+/// | 0      | 32   | `lobby_id_hash`      |
+/// | 32     | 32   | `host`               |
+/// | 64     | 32   | `opponent`           |
+/// | 96     | 1    | `host_deposited`     |
+/// | 97     | 1    | `opponent_deposited` |
+/// | 98     | 1    | `settled`            |
+/// | 99     | 1    | `disputed`           |
+/// | 100    | 32   | `winner`             |
+/// | 132    | 8    | `total_pot` (u64 LE) |
+///
+/// New fields are only ever appended.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EscrowSnapshot {
+    pub lobby_id_hash: [u8; 32],
+    pub host: Pubkey,
+    pub opponent: Pubkey,
+    pub host_deposited: bool,
+    pub opponent_deposited: bool,
+    pub settled: bool,
+    pub disputed: bool,
+    /// Default pubkey until a winner is paid or recorded
+    pub winner: Pubkey,
+    /// Deposits plus any bonus currently held for payout
+    pub total_pot: u64,
+}
+
+/// Read-only status query for composing programs. The snapshot is set as
+/// return data, so a CPI caller reads it with `get_return_data` (or
+/// `cpi_helpers::get_state`).
+#[derive(Accounts)]
+pub struct GetState<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<GetState>) -> Result<EscrowSnapshot> {
+    let escrow = &ctx.accounts.escrow;
+
+    Ok(EscrowSnapshot {
+        lobby_id_hash: escrow.lobby_id_hash,
+        host: escrow.host,
+        opponent: escrow.opponent,
+        host_deposited: escrow.host_deposited,
+        opponent_deposited: escrow.opponent_deposited,
+        settled: escrow.settled,
+        disputed: escrow.disputed,
+        winner: escrow.winner,
+        total_pot: escrow.total_pot()?,
+    })
+}
@@ -1,46 +1,535 @@
 use anchor_lang::prelude::*;
-use crate::constants::ESCROW_SEED;
-use crate::state::MatchEscrow;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constants::{
+    CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION, FEE_BPS, MAX_ESCROW_LIFETIME_SECS, MAX_FEE_BPS,
+    MAX_WAGER_LAMPORTS, MIN_WAGER_LAMPORTS,
+};
+use crate::error::EscrowError;
+use crate::events::EscrowInitialized;
+use crate::state::{DepositMethod, EscrowStatus, MatchEscrow, ProgramConfig, RoundingMode};
+
+/// Arguments for `initialize_escrow`. Borsh-encodes field by field, so the
+/// instruction data layout is the same as passing each value positionally.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeEscrowParams {
+    /// PDA seed, unique per match. Derive it with
+    /// [`lobby_seed`](crate::state::lobby_seed) so replays of a lobby never
+    /// reuse a just-closed escrow address.
+    pub lobby_id_hash: [u8; 32],
+    pub host: Pubkey,
+    /// `Pubkey::default()` opens the seat to whoever `join`s first
+    pub opponent: Pubkey,
+    /// Host stake in atomic units (lamports or token base units)
+    pub host_wager: u64,
+    /// Opponent stake in atomic units (lamports or token base units)
+    pub opponent_wager: u64,
+    /// Pubkey::default() = native SOL, otherwise SPL mint
+    pub token_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub deposit_deadline: i64,
+    /// Defaults to `FEE_BPS` when omitted
+    pub fee_bps: Option<u16>,
+    pub match_deadline: i64,
+    pub rounds_to_win: u8,
+    /// Allows a zero wager for either side (sponsored/free-entry matches)
+    pub free_entry: bool,
+    /// Per-player minimum stake in base units. Native SOL defaults to
+    /// `MIN_WAGER_LAMPORTS`; SPL escrows are unbounded unless set.
+    pub min_wager: Option<u64>,
+    /// Per-player maximum stake in base units. Native SOL defaults to
+    /// `MAX_WAGER_LAMPORTS`; SPL escrows are unbounded unless set.
+    pub max_wager: Option<u64>,
+    /// Keep the escrow open after settlement (outcome readable on-chain)
+    /// until the authority calls `sweep_closed`
+    pub retain_record: bool,
+    /// Slot-based deposit deadline. Set together with `match_deadline_slot`
+    /// to enforce slots instead of the unix timestamp deadlines.
+    pub deposit_deadline_slot: Option<u64>,
+    /// Slot-based match deadline. Set together with `deposit_deadline_slot`.
+    pub match_deadline_slot: Option<u64>,
+    /// Route `settle`/`forfeit` fees to the treasury in `ProgramConfig`
+    pub use_global_treasury: bool,
+    /// Only allow server-orchestrated deposits co-signed by the authority
+    pub require_authorized_deposit: bool,
+    /// Informational reference for support tooling; never read on-chain
+    pub match_ref: Option<[u8; 32]>,
+    /// After this, a funded escrow that was never settled can be refunded
+    /// in full via `auto_refund`
+    pub settlement_deadline: i64,
+    /// External score oracle whose ed25519 signature can authorize the
+    /// winner via `settle_with_oracle_sig`
+    pub oracle: Option<Pubkey>,
+    /// Affiliate who receives `referral_bps` of the pot out of the fee
+    pub referrer: Option<Pubkey>,
+    /// Referrer's cut in basis points; must not exceed the escrow fee
+    pub referral_bps: u16,
+    /// Fee `RoundingMode` discriminant; 0 (floor) matches earlier escrows
+    pub rounding: u8,
+    /// Restrict `deposit` to holders of a verified NFT from this collection
+    pub required_collection: Option<Pubkey>,
+    /// Signer for `settle`/`forfeit`; defaults to the authority
+    pub settle_authority: Option<Pubkey>,
+    /// Permit `treasury` to be one of the players, e.g. house-vs-player
+    /// matches where the house collects its own fee
+    pub allow_player_treasury: bool,
+    /// Share of the pot burned out of the fee at settlement. SPL mints only;
+    /// together with `referral_bps` it must not exceed the fee
+    pub burn_bps: u16,
+    /// Minimum fee in base units, so tiny wagers still cover operating
+    /// costs. Never takes more than half the pot.
+    pub min_fee: u64,
+    /// Maximum fee in base units, so large wagers pay a bounded rake.
+    /// 0 = no cap; otherwise must be at least `min_fee`.
+    pub max_fee: u64,
+    /// Seconds that must pass after both deposits land before the match
+    /// can be settled. 0 = no minimum.
+    pub min_match_duration: i64,
+    /// Player paid by the permissionless `crank_settle` if the match is
+    /// still unsettled after `settlement_deadline`. Must be a seated player;
+    /// not allowed with a referrer or burn.
+    pub default_winner: Option<Pubkey>,
+    /// Cranker's share of the fee in basis points (at most 10,000)
+    pub crank_reward_bps: u16,
+    /// Require the authority to co-sign `join`, so the server can vet who
+    /// takes the open seat. Only valid with an open seat.
+    pub require_join_approval: bool,
+    /// Platform-funded free roll: the authority stakes both seats via
+    /// `sponsor_deposit` and no fee is taken, overriding the fee params.
+    /// Both players must be seated.
+    pub sponsored: bool,
+}
 
 #[derive(Accounts)]
-#[instruction(lobby_id_hash: [u8; 32])]
+#[instruction(params: InitializeEscrowParams)]
 pub struct InitializeEscrow<'info> {
-    #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Funds the escrow's rent and gets it back when a payout closes the
+    /// account. May be the authority itself or a player.
+    #[account(mut, constraint = covers_escrow_rent(&payer)? @ EscrowError::InsufficientRent)]
+    pub payer: Signer<'info>,
+
     #[account(
         init,
-        payer = authority,
+        payer = payer,
         space = 8 + MatchEscrow::INIT_SPACE,
-        seeds = [ESCROW_SEED, lobby_id_hash.as_ref()],
+        seeds = [ESCROW_SEED, params.lobby_id_hash.as_ref()],
         bump,
     )]
     pub escrow: Account<'info, MatchEscrow>,
 
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// `initialize_escrow` for SPL escrows that also creates the escrow PDA's
+/// associated token account, so the first deposit never races a separate
+/// ATA-creation transaction. Native SOL escrows keep using
+/// `initialize_escrow`.
+#[derive(Accounts)]
+#[instruction(params: InitializeEscrowParams)]
+pub struct InitializeEscrowSpl<'info> {
+    pub authority: Signer<'info>,
+
+    /// Funds the escrow's and its token account's rent. The escrow's rent
+    /// comes back when a payout closes it.
+    #[account(mut, constraint = covers_escrow_rent(&payer)? @ EscrowError::InsufficientRent)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MatchEscrow::INIT_SPACE,
+        seeds = [ESCROW_SEED, params.lobby_id_hash.as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        address = params.token_mint @ EscrowError::WrongMint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Anyone can create a PDA's ATA ahead of time, so an existing one is
+    /// accepted rather than letting a front-run creation block the init.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program (legacy SPL Token or Token-2022) that owns `mint`.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<InitializeEscrow>,
-    lobby_id_hash: [u8; 32],
-    host: Pubkey,
-    opponent: Pubkey,
-    wager_lamports: u64,
-    token_mint: Pubkey,
-    treasury: Pubkey,
+/// Whether `payer` can cover the escrow's rent. Checked ahead of `init` so
+/// a short payer gets `InsufficientRent` instead of a system program error.
+fn covers_escrow_rent(payer: &AccountInfo) -> Result<bool> {
+    let rent = Rent::get()?.minimum_balance(8 + MatchEscrow::INIT_SPACE);
+    Ok(payer.lamports() >= rent)
+}
+
+pub fn handler(ctx: Context<InitializeEscrow>, params: InitializeEscrowParams) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    let authority = ctx.accounts.authority.key();
+    let rent_payer = ctx.accounts.payer.key();
+    let escrow_key = ctx.accounts.escrow.key();
+    let bump = ctx.bumps.escrow;
+    init_escrow(
+        &mut ctx.accounts.escrow,
+        escrow_key,
+        authority,
+        rent_payer,
+        bump,
+        params,
+    )
+}
+
+pub fn spl_handler(ctx: Context<InitializeEscrowSpl>, params: InitializeEscrowParams) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    let authority = ctx.accounts.authority.key();
+    let rent_payer = ctx.accounts.payer.key();
+    let escrow_key = ctx.accounts.escrow.key();
+    let bump = ctx.bumps.escrow;
+    init_escrow(
+        &mut ctx.accounts.escrow,
+        escrow_key,
+        authority,
+        rent_payer,
+        bump,
+        params,
+    )
+}
+
+/// Validates `params` and writes a fresh escrow. Shared by both init
+/// instructions.
+fn init_escrow(
+    escrow: &mut MatchEscrow,
+    escrow_key: Pubkey,
+    authority: Pubkey,
+    rent_payer: Pubkey,
+    bump: u8,
+    params: InitializeEscrowParams,
 ) -> Result<()> {
-    let escrow = &mut ctx.accounts.escrow;
-    escrow.lobby_id_hash = lobby_id_hash;
-    escrow.host = host;
-    escrow.opponent = opponent;
-    escrow.wager_lamports = wager_lamports;
-    escrow.token_mint = token_mint;
-    escrow.treasury = treasury;
-    escrow.authority = ctx.accounts.authority.key();
+    // Sponsored pots go to the winner whole
+    let fee_bps = if params.sponsored {
+        0
+    } else {
+        params.fee_bps.unwrap_or(FEE_BPS)
+    };
+    let min_fee = if params.sponsored { 0 } else { params.min_fee };
+    require!(fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+    require!(
+        params
+            .referral_bps
+            .checked_add(params.burn_bps)
+            .is_some_and(|carved| carved <= fee_bps),
+        EscrowError::FeeTooHigh
+    );
+    require!(
+        params.max_fee == 0 || params.max_fee >= params.min_fee,
+        EscrowError::InvalidFeeBounds
+    );
+    if let Some(default_winner) = params.default_winner {
+        require!(
+            default_winner != Pubkey::default()
+                && (default_winner == params.host || default_winner == params.opponent)
+                && params.referrer.is_none()
+                && params.burn_bps == 0,
+            EscrowError::InvalidDefaultWinner
+        );
+    }
+    require!(params.crank_reward_bps <= 10_000, EscrowError::FeeTooHigh);
+    require!(
+        !params.require_join_approval || params.opponent == Pubkey::default(),
+        EscrowError::SlotTaken
+    );
+    require!(
+        !params.sponsored || params.opponent != Pubkey::default(),
+        EscrowError::SponsoredEscrow
+    );
+    require!(
+        RoundingMode::from_u8(params.rounding).is_some(),
+        EscrowError::InvalidRoundingMode
+    );
+    require!(params.rounds_to_win >= 1, EscrowError::InvalidRoundsToWin);
+    // A default opponent leaves the seat open for `join`
+    require!(params.host != Pubkey::default(), EscrowError::DuplicatePlayer);
+    require!(params.host != params.opponent, EscrowError::DuplicatePlayer);
+    // A player-owned treasury would hand the fee back to that player
+    require!(params.treasury != escrow_key, EscrowError::InvalidTreasury);
+    require!(
+        params.allow_player_treasury
+            || (params.treasury != params.host && params.treasury != params.opponent),
+        EscrowError::InvalidTreasury
+    );
+    require!(
+        params.free_entry || (params.host_wager > 0 && params.opponent_wager > 0),
+        EscrowError::ZeroWager
+    );
+
+    let is_native = params.token_mint == Pubkey::default();
+    // Lamports can't be burned, and the token program refuses to burn WSOL
+    require!(
+        params.burn_bps == 0 || !(is_native || params.token_mint == native_mint::ID),
+        EscrowError::BurnRequiresSpl
+    );
+    let min_wager = params
+        .min_wager
+        .unwrap_or(if is_native { MIN_WAGER_LAMPORTS } else { 0 });
+    let max_wager = params
+        .max_wager
+        .unwrap_or(if is_native { MAX_WAGER_LAMPORTS } else { u64::MAX });
+    for wager in [params.host_wager, params.opponent_wager] {
+        // A zero stake on a free-entry escrow is exempt from the minimum
+        if !(params.free_entry && wager == 0) {
+            require!(wager >= min_wager, EscrowError::WagerTooSmall);
+        }
+        require!(wager <= max_wager, EscrowError::WagerTooLarge);
+    }
+
+    // Deadlines are either all timestamps or all slots, never a mix
+    let (slot_deadlines, deposit_deadline_slot, match_deadline_slot) =
+        match (params.deposit_deadline_slot, params.match_deadline_slot) {
+            (None, None) => (false, 0, 0),
+            (Some(deposit), Some(matched)) => (true, deposit, matched),
+            _ => return err!(EscrowError::MixedDeadlineModes),
+        };
+
+    escrow.lobby_id_hash = params.lobby_id_hash;
+    escrow.host = params.host;
+    escrow.opponent = params.opponent;
+    escrow.host_wager = params.host_wager;
+    escrow.opponent_wager = params.opponent_wager;
+    escrow.token_mint = params.token_mint;
+    escrow.treasury = params.treasury;
+    escrow.authority = authority;
     escrow.host_deposited = false;
     escrow.opponent_deposited = false;
+    escrow.host_deposited_amount = 0;
+    escrow.opponent_deposited_amount = 0;
+    escrow.host_deposit_method = DepositMethod::None as u8;
+    escrow.opponent_deposit_method = DepositMethod::None as u8;
+    escrow.pending_payout = 0;
+    escrow.payout_claimed = false;
+    escrow.first_depositor = Pubkey::default();
+    escrow.last_confirm_nonce = 0;
+    escrow.funded_at = 0;
+    escrow.bonus_pool = 0;
     escrow.settled = false;
-    escrow.bump = ctx.bumps.escrow;
+    escrow.bump = bump;
+    escrow.deposit_deadline = params.deposit_deadline;
+    escrow.fee_bps = fee_bps;
+    escrow.match_deadline = params.match_deadline;
+    escrow.rounds_to_win = params.rounds_to_win;
+    escrow.host_wins = 0;
+    escrow.opponent_wins = 0;
+    escrow.disputed = false;
+    escrow.result_commitment = [0u8; 32];
+    escrow.result_committer = Pubkey::default();
+    escrow.reveal_deadline = 0;
+    escrow.free_entry = params.free_entry;
+    escrow.settled_at = 0;
+    escrow.winner = Pubkey::default();
+    escrow.retain_record = params.retain_record;
+    escrow.host_payment_ref = [0u8; 32];
+    escrow.opponent_payment_ref = [0u8; 32];
+    escrow.slot_deadlines = slot_deadlines;
+    escrow.deposit_deadline_slot = deposit_deadline_slot;
+    escrow.match_deadline_slot = match_deadline_slot;
+    escrow.use_global_treasury = params.use_global_treasury;
+    escrow.require_authorized_deposit = params.require_authorized_deposit;
+    escrow.rent_payer = rent_payer;
+    escrow.match_ref = params.match_ref.unwrap_or([0u8; 32]);
+    escrow.version = ESCROW_VERSION;
+    escrow.settling = false;
+    escrow.settlement_deadline = params.settlement_deadline;
+    escrow.expires_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(MAX_ESCROW_LIFETIME_SECS)
+        .ok_or(EscrowError::NotYetExpired)?;
+    escrow.oracle = params.oracle.unwrap_or_default();
+    escrow.referrer = params.referrer.unwrap_or_default();
+    escrow.referral_bps = params.referral_bps;
+    escrow.rounding = params.rounding;
+    escrow.burn_bps = params.burn_bps;
+    escrow.min_fee = min_fee;
+    escrow.allow_player_treasury = params.allow_player_treasury;
+    escrow.max_fee = params.max_fee;
+    escrow.min_match_duration = params.min_match_duration;
+    escrow.default_winner = params.default_winner.unwrap_or_default();
+    escrow.crank_reward_bps = params.crank_reward_bps;
+    escrow.require_join_approval = params.require_join_approval;
+    escrow.sponsored = params.sponsored;
+    escrow.status = EscrowStatus::Created as u8;
+    escrow.required_collection = params.required_collection.unwrap_or_default();
+    escrow.proposed_winner = Pubkey::default();
+    escrow.challenge_deadline = 0;
+    escrow.settle_authority = params.settle_authority.unwrap_or(escrow.authority);
+
+    emit!(EscrowInitialized {
+        lobby_id_hash: params.lobby_id_hash,
+        escrow: escrow_key,
+        bump,
+        host: params.host,
+        opponent: params.opponent,
+        host_wager: params.host_wager,
+        opponent_wager: params.opponent_wager,
+        token_mint: params.token_mint,
+        treasury: params.treasury,
+        authority: escrow.authority,
+        deposit_deadline: params.deposit_deadline,
+        fee_bps,
+        match_deadline: params.match_deadline,
+        rounds_to_win: params.rounds_to_win,
+        free_entry: params.free_entry,
+        retain_record: params.retain_record,
+        slot_deadlines,
+        deposit_deadline_slot,
+        match_deadline_slot,
+        use_global_treasury: params.use_global_treasury,
+        require_authorized_deposit: params.require_authorized_deposit,
+        rent_payer: escrow.rent_payer,
+        match_ref: escrow.match_ref,
+        settlement_deadline: params.settlement_deadline,
+        oracle: escrow.oracle,
+        referrer: escrow.referrer,
+        referral_bps: params.referral_bps,
+        rounding: params.rounding,
+        required_collection: escrow.required_collection,
+        settle_authority: escrow.settle_authority,
+        burn_bps: params.burn_bps,
+        min_fee,
+        max_fee: params.max_fee,
+        min_match_duration: params.min_match_duration,
+        default_winner: escrow.default_winner,
+        crank_reward_bps: params.crank_reward_bps,
+        require_join_approval: params.require_join_approval,
+        sponsored: params.sponsored,
+    });
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> InitializeEscrowParams {
+        InitializeEscrowParams {
+            lobby_id_hash: [1u8; 32],
+            host: Pubkey::new_unique(),
+            opponent: Pubkey::new_unique(),
+            host_wager: MIN_WAGER_LAMPORTS,
+            opponent_wager: MIN_WAGER_LAMPORTS,
+            token_mint: Pubkey::default(),
+            treasury: Pubkey::new_unique(),
+            deposit_deadline: 1_000,
+            fee_bps: None,
+            match_deadline: 2_000,
+            rounds_to_win: 1,
+            free_entry: false,
+            min_wager: None,
+            max_wager: None,
+            retain_record: false,
+            deposit_deadline_slot: None,
+            match_deadline_slot: None,
+            use_global_treasury: false,
+            require_authorized_deposit: false,
+            match_ref: None,
+            settlement_deadline: 3_000,
+            oracle: None,
+            referrer: None,
+            referral_bps: 0,
+            rounding: 0,
+            required_collection: None,
+            settle_authority: None,
+            allow_player_treasury: false,
+            burn_bps: 0,
+            min_fee: 0,
+            max_fee: 0,
+            min_match_duration: 0,
+            default_winner: None,
+            crank_reward_bps: 0,
+            require_join_approval: false,
+            sponsored: false,
+        }
+    }
+
+    fn init(params: InitializeEscrowParams) -> Result<()> {
+        let data = [0u8; MatchEscrow::INIT_SPACE];
+        let mut escrow = MatchEscrow::deserialize(&mut &data[..]).unwrap();
+        let (escrow_key, bump) = MatchEscrow::pda(&params.lobby_id_hash, &crate::ID);
+        let authority = Pubkey::new_unique();
+        init_escrow(&mut escrow, escrow_key, authority, authority, bump, params)
+    }
+
+    /// Valid params get through validation to the clock read, which has no
+    /// sysvar to read outside the runtime.
+    fn assert_passes_validation(params: InitializeEscrowParams) {
+        assert_eq!(init(params).unwrap_err(), ProgramError::UnsupportedSysvar.into());
+    }
+
+    #[test]
+    fn valid_params_pass_validation() {
+        assert_passes_validation(params());
+    }
+
+    #[test]
+    fn self_match_is_rejected() {
+        let mut p = params();
+        p.opponent = p.host;
+        assert_eq!(init(p).unwrap_err(), EscrowError::DuplicatePlayer.into());
+    }
+
+    #[test]
+    fn default_host_is_rejected() {
+        let mut p = params();
+        p.host = Pubkey::default();
+        assert_eq!(init(p).unwrap_err(), EscrowError::DuplicatePlayer.into());
+    }
+
+    #[test]
+    fn player_treasury_is_rejected_without_the_override() {
+        let mut p = params();
+        p.treasury = p.host;
+        assert_eq!(init(p).unwrap_err(), EscrowError::InvalidTreasury.into());
+
+        let mut p = params();
+        p.treasury = p.opponent;
+        assert_eq!(init(p).unwrap_err(), EscrowError::InvalidTreasury.into());
+    }
+
+    #[test]
+    fn escrow_as_treasury_is_rejected() {
+        let mut p = params();
+        p.treasury = MatchEscrow::pda(&p.lobby_id_hash, &crate::ID).0;
+        p.allow_player_treasury = true;
+        assert_eq!(init(p).unwrap_err(), EscrowError::InvalidTreasury.into());
+    }
+
+    #[test]
+    fn player_treasury_is_allowed_with_the_override() {
+        let mut p = params();
+        p.treasury = p.host;
+        p.allow_player_treasury = true;
+        assert_passes_validation(p);
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::error::EscrowError;
+use crate::events::OpponentJoined;
+use crate::instructions::deposit::{self, credited_player, Deposit};
+
+/// Claims the open opponent seat of an escrow created with
+/// `opponent = Pubkey::default()`, then deposits the opponent's wager as
+/// `deposit` would. Takes the `Deposit` accounts. The first non-host player
+/// to land takes the seat; later joiners fail with `SlotTaken`.
+///
+/// Escrows created with `require_join_approval` only seat a joiner the
+/// authority co-signs for, passed as `authority` in the `Deposit` accounts.
+pub fn handler(ctx: Context<Deposit>) -> Result<()> {
+    let joiner = credited_player(ctx.accounts)?;
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(escrow.opponent == Pubkey::default(), EscrowError::SlotTaken);
+    require!(joiner != escrow.host, EscrowError::DuplicatePlayer);
+    // The treasury check at init could not see the joiner
+    if !escrow.allow_player_treasury {
+        let treasury = if escrow.use_global_treasury {
+            ctx.accounts.config.treasury
+        } else {
+            escrow.treasury
+        };
+        require!(joiner != treasury, EscrowError::InvalidTreasury);
+    }
+    if escrow.require_join_approval {
+        let authority = ctx
+            .accounts
+            .authority
+            .as_ref()
+            .ok_or(EscrowError::JoinNotApproved)?;
+        require!(
+            authority.key() == escrow.authority,
+            EscrowError::JoinNotApproved
+        );
+    }
+
+    escrow.opponent = joiner;
+
+    emit!(OpponentJoined {
+        lobby_id_hash: escrow.lobby_id_hash,
+        opponent: joiner,
+    });
+
+    deposit::handler(ctx)
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::constants::{ESCROW_VERSION, MAX_ESCROW_LIFETIME_SECS};
+use crate::error::EscrowError;
+use crate::events::EscrowMigrated;
+use crate::state::{EscrowStatus, MatchEscrow};
+
+/// Authority-only upgrade of an escrow written with an older layout.
+/// Grows the account to the current size (topping up rent from the
+/// authority), fills the new fields with defaults and stamps
+/// `ESCROW_VERSION`. The account is taken unchecked because an old layout
+/// can't deserialize as `Account<MatchEscrow>`.
+#[derive(Accounts)]
+pub struct MigrateEscrow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Owner checked here; discriminator, PDA and authority in handler.
+    #[account(mut, owner = crate::ID)]
+    pub escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateEscrow>) -> Result<()> {
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    let target_len = 8 + MatchEscrow::INIT_SPACE;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    let lobby_id_hash: [u8; 32] = {
+        let data = escrow_info.try_borrow_data()?;
+        require!(
+            data.len() >= 40 && data[..8] == *MatchEscrow::DISCRIMINATOR,
+            EscrowError::UnsupportedVersion
+        );
+        data[8..40].try_into().unwrap()
+    };
+    let (expected, _) = MatchEscrow::pda(&lobby_id_hash, ctx.program_id);
+    require!(escrow_info.key() == expected, EscrowError::NotAuthorized);
+    require!(
+        escrow_info.data_len() <= target_len,
+        EscrowError::UnsupportedVersion
+    );
+
+    // ---------------------------------------------------------------
+    // Grow the account; new bytes are zeroed, which is the default for
+    // every appended field
+    // ---------------------------------------------------------------
+    let required = Rent::get()?.minimum_balance(target_len);
+    let shortfall = required.saturating_sub(escrow_info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: escrow_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    escrow_info.resize(target_len)?;
+
+    let mut escrow = MatchEscrow::try_deserialize(&mut &escrow_info.try_borrow_data()?[..])?;
+    require!(
+        escrow.authority == ctx.accounts.authority.key(),
+        EscrowError::NotAuthorized
+    );
+    require!(escrow.version < ESCROW_VERSION, EscrowError::AlreadyMigrated);
+
+    // ---------------------------------------------------------------
+    // Fill defaults that zero bytes can't express
+    // ---------------------------------------------------------------
+    let from_version = escrow.version;
+    if escrow.rent_payer == Pubkey::default() {
+        escrow.rent_payer = escrow.authority;
+    }
+    if escrow.settle_authority == Pubkey::default() {
+        escrow.settle_authority = escrow.authority;
+    }
+    // Older escrows get a full lifetime starting now
+    if escrow.expires_at == 0 {
+        escrow.expires_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(MAX_ESCROW_LIFETIME_SECS)
+            .ok_or(EscrowError::NotYetExpired)?;
+    }
+    // Deposits recorded before amount tracking were always the exact wager
+    if escrow.host_deposited && escrow.host_deposited_amount == 0 {
+        escrow.host_deposited_amount = escrow.host_wager;
+    }
+    if escrow.opponent_deposited && escrow.opponent_deposited_amount == 0 {
+        escrow.opponent_deposited_amount = escrow.opponent_wager;
+    }
+    // Derive the lifecycle stage from the flags it replaces. Retained
+    // records with no winner were refunds.
+    let status = if escrow.settled && escrow.winner != Pubkey::default() {
+        EscrowStatus::Settled
+    } else if escrow.settled {
+        EscrowStatus::Refunded
+    } else if escrow.disputed && escrow.both_funded() {
+        EscrowStatus::Disputed
+    } else {
+        escrow.funding_status()
+    };
+    escrow.status = status as u8;
+    escrow.version = ESCROW_VERSION;
+
+    escrow.try_serialize(&mut &mut escrow_info.try_borrow_mut_data()?[..])?;
+
+    emit!(EscrowMigrated {
+        lobby_id_hash,
+        from_version,
+        to_version: ESCROW_VERSION,
+    });
+
+    Ok(())
+}
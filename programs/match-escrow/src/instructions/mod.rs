@@ -3,9 +3,89 @@ pub mod deposit;
 pub mod settle;
 pub mod forfeit;
 pub mod confirm_deposit;
+pub mod reclaim_unfunded;
+pub mod settle_draw;
+pub mod cancel;
+pub mod claim_timeout_win;
+pub mod report_round;
+pub mod settle_signed;
+pub mod dispute;
+pub mod commit_reveal;
+pub mod transfer_authority;
+pub mod quote_settlement;
+pub mod refund_no_show;
+pub mod sweep_closed;
+pub mod multi_escrow;
+pub mod settle_ranked;
+pub mod config;
+pub mod rematch;
+pub mod settle_batch;
+pub mod withdraw_stuck_funds;
+pub mod migrate_escrow;
+pub mod auto_refund;
+pub mod update_wager;
+pub mod settle_oracle;
+pub mod settle_double_forfeit;
+pub mod deposit_both;
+pub mod pending_payout;
+pub mod challenge;
+pub mod expire_refund;
+pub mod add_bonus;
+pub mod require_funded;
+pub mod get_state;
+pub mod result_log;
+pub mod join;
+pub mod settle_tiebreak;
+pub mod cross_escrow;
+pub mod settle_agreed;
+pub mod mutual_cancel;
+pub mod emergency_refund;
+pub mod fee_vault;
+pub mod player_index;
+pub mod series_escrow;
+pub mod crank_settle;
+pub mod sponsor_deposit;
 
 pub use initialize::*;
 pub use deposit::*;
 pub use settle::*;
-pub use forfeit::*;
 pub use confirm_deposit::*;
+pub use reclaim_unfunded::*;
+pub use settle_draw::*;
+pub use cancel::*;
+pub use report_round::*;
+pub use settle_signed::*;
+pub use dispute::*;
+pub use commit_reveal::*;
+pub use transfer_authority::*;
+pub use quote_settlement::*;
+pub use refund_no_show::*;
+pub use sweep_closed::*;
+pub use multi_escrow::*;
+pub use settle_ranked::*;
+pub use config::*;
+pub use rematch::*;
+pub use settle_batch::*;
+pub use withdraw_stuck_funds::*;
+pub use migrate_escrow::*;
+pub use auto_refund::*;
+pub use update_wager::*;
+pub use settle_oracle::*;
+pub use settle_double_forfeit::*;
+pub use deposit_both::*;
+pub use pending_payout::*;
+pub use challenge::*;
+pub use expire_refund::*;
+pub use add_bonus::*;
+pub use require_funded::*;
+pub use get_state::*;
+pub use result_log::*;
+pub use cross_escrow::*;
+pub use settle_agreed::*;
+pub use mutual_cancel::*;
+pub use emergency_refund::*;
+pub use fee_vault::*;
+pub use player_index::*;
+pub use series_escrow::*;
+pub use crank_settle::*;
+pub use sponsor_deposit::*;
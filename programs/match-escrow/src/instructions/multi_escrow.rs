@@ -0,0 +1,486 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{
+    CONFIG_SEED, FEE_BPS, MAX_FEE_BPS, MAX_PLAYERS, MAX_WAGER_LAMPORTS, MIN_WAGER_LAMPORTS,
+    MULTI_ESCROW_SEED,
+};
+use crate::error::EscrowError;
+use crate::events::{MultiDepositMade, MultiEscrowInitialized, MultiEscrowSettled};
+use crate::state::{MultiMatchEscrow, ProgramConfig};
+
+/// Arguments for `initialize_multi_escrow`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeMultiEscrowParams {
+    /// SHA-256 hash of the Convex lobby ID string (PDA seed)
+    pub lobby_id_hash: [u8; 32],
+    /// Seated players, 2 to `MAX_PLAYERS`, no duplicates
+    pub players: Vec<Pubkey>,
+    /// Per-player stake in atomic units (lamports or token base units)
+    pub wager: u64,
+    /// Pubkey::default() = native SOL, otherwise SPL mint
+    pub token_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub deposit_deadline: i64,
+    /// Defaults to `FEE_BPS` when omitted
+    pub fee_bps: Option<u16>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: InitializeMultiEscrowParams)]
+pub struct InitializeMultiEscrow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MultiMatchEscrow::INIT_SPACE,
+        seeds = [MULTI_ESCROW_SEED, params.lobby_id_hash.as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, MultiMatchEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves one seated player's wager into a multiplayer escrow. The seat is
+/// passed as `player_index` and must belong to the signer.
+#[derive(Accounts)]
+pub struct DepositMulti<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MULTI_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MultiMatchEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Depositor's token account (only needed for SPL deposits).
+    #[account(mut)]
+    pub depositor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account, only needed for SPL deposits. Must be the
+    /// escrow PDA's associated token account for `token_mint`.
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL deposits.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL deposits.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only payout of a multiplayer pot to a single winner, minus the
+/// treasury fee. Closes the escrow to the authority.
+#[derive(Accounts)]
+pub struct SettleMulti<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MULTI_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, MultiMatchEscrow>,
+
+    /// CHECK: Validated against escrow.players in handler.
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against escrow.treasury in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Winner's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account (only needed for SPL settlements with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL settlements.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL settlements.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_handler(
+    ctx: Context<InitializeMultiEscrow>,
+    params: InitializeMultiEscrowParams,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    let fee_bps = params.fee_bps.unwrap_or(FEE_BPS);
+    require!(fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+
+    let player_count = params.players.len();
+    require!(
+        (2..=MAX_PLAYERS).contains(&player_count),
+        EscrowError::InvalidPlayerCount
+    );
+    for (i, player) in params.players.iter().enumerate() {
+        require!(
+            !params.players[..i].contains(player),
+            EscrowError::InvalidPlayerCount
+        );
+    }
+
+    require!(params.wager > 0, EscrowError::ZeroWager);
+    if params.token_mint == Pubkey::default() {
+        require!(
+            params.wager >= MIN_WAGER_LAMPORTS,
+            EscrowError::WagerTooSmall
+        );
+        require!(
+            params.wager <= MAX_WAGER_LAMPORTS,
+            EscrowError::WagerTooLarge
+        );
+    }
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.lobby_id_hash = params.lobby_id_hash;
+    escrow.players = params.players.clone();
+    escrow.deposited = vec![false; player_count];
+    escrow.wager = params.wager;
+    escrow.token_mint = params.token_mint;
+    escrow.treasury = params.treasury;
+    escrow.authority = ctx.accounts.authority.key();
+    escrow.settled = false;
+    escrow.bump = ctx.bumps.escrow;
+    escrow.deposit_deadline = params.deposit_deadline;
+    escrow.fee_bps = fee_bps;
+
+    emit!(MultiEscrowInitialized {
+        lobby_id_hash: params.lobby_id_hash,
+        players: params.players,
+        wager: params.wager,
+        token_mint: params.token_mint,
+        treasury: params.treasury,
+        authority: escrow.authority,
+        deposit_deadline: params.deposit_deadline,
+        fee_bps,
+    });
+
+    Ok(())
+}
+
+pub fn deposit_handler(ctx: Context<DepositMulti>, player_index: u8) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    // ---------------------------------------------------------------
+    // Extract all needed values BEFORE any CPI calls (avoids E0502).
+    // ---------------------------------------------------------------
+    let depositor_key = ctx.accounts.depositor.key();
+    let seat = player_index as usize;
+    let seated = ctx.accounts.escrow.players.get(seat).copied();
+    let already_deposited = ctx.accounts.escrow.deposited.get(seat).copied();
+    let settled = ctx.accounts.escrow.settled;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let escrow_key = ctx.accounts.escrow.key();
+    let deposit_deadline = ctx.accounts.escrow.deposit_deadline;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let amount = ctx.accounts.escrow.wager;
+
+    require!(
+        seated == Some(depositor_key),
+        EscrowError::InvalidPlayerIndex
+    );
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        Clock::get()?.unix_timestamp <= deposit_deadline,
+        EscrowError::DepositWindowClosed
+    );
+    require!(
+        already_deposited == Some(false),
+        EscrowError::AlreadyDeposited
+    );
+
+    profile!("deposit_multi", "validated");
+
+    // ---------------------------------------------------------------
+    // Transfer funds into the escrow
+    // ---------------------------------------------------------------
+    if is_native {
+        let balance_before = ctx.accounts.escrow.to_account_info().lamports();
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let received = ctx
+            .accounts
+            .escrow
+            .to_account_info()
+            .lamports()
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        require!(received == amount, EscrowError::DepositShortfall);
+    } else {
+        let depositor_ta = ctx
+            .accounts
+            .depositor_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            depositor_ta.mint == token_mint,
+            EscrowError::WrongMint
+        );
+        require!(escrow_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.owner == escrow_key,
+            EscrowError::InvalidEscrowTokenAccount
+        );
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &escrow_key,
+                    &token_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+
+        let balance_before = escrow_ta.amount;
+
+        let cpi_ctx = CpiContext::new(
+            token_prog.to_account_info(),
+            TransferChecked {
+                from: depositor_ta.to_account_info(),
+                mint: mint.to_account_info(),
+                to: escrow_ta.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        escrow_ta.reload()?;
+        let received = escrow_ta
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        require!(received == amount, EscrowError::DepositShortfall);
+    }
+
+    // ---------------------------------------------------------------
+    // Mark deposit flag (mutable borrow begins here, after all CPI)
+    // ---------------------------------------------------------------
+    ctx.accounts.escrow.deposited[seat] = true;
+
+    emit!(MultiDepositMade {
+        lobby_id_hash,
+        player_index,
+        depositor: depositor_key,
+        amount,
+    });
+
+    Ok(())
+}
+
+pub fn settle_handler(ctx: Context<SettleMulti>, winner: Pubkey) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // Avoids E0502 when we need &mut ctx.accounts.escrow later.
+    // ---------------------------------------------------------------
+    let is_player = ctx.accounts.escrow.players.contains(&winner);
+    let fully_funded = ctx.accounts.escrow.fully_funded();
+    let settled = ctx.accounts.escrow.settled;
+    let total_pot = ctx.accounts.escrow.total_pot()?;
+    let fee_bps = ctx.accounts.escrow.fee_bps;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let treasury_key = ctx.accounts.escrow.treasury;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(is_player, EscrowError::InvalidWinner);
+    require!(fully_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        ctx.accounts.winner.key() == winner,
+        EscrowError::InvalidWinner
+    );
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+
+    // ---------------------------------------------------------------
+    // Calculate distribution: fee_bps to treasury, remainder to winner
+    // Uses u128 intermediate to avoid overflow on large pots.
+    // ---------------------------------------------------------------
+    let fee = (total_pot as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(EscrowError::InsufficientFunds)?
+        .checked_div(10_000)
+        .ok_or(EscrowError::InsufficientFunds)? as u64;
+    let payout = total_pot
+        .checked_sub(fee)
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    profile!("settle_multi", "validated");
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[MULTI_ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Transfer funds
+    // ---------------------------------------------------------------
+    if is_native {
+        // Native SOL: direct lamport manipulation (PDA owns the lamports).
+        // Remaining rent-exempt lamports are reclaimed by `close = authority`.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let winner_info = ctx.accounts.winner.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= payout;
+        **winner_info.try_borrow_mut_lamports()? += payout;
+
+        **escrow_info.try_borrow_mut_lamports()? -= fee;
+        **treasury_info.try_borrow_mut_lamports()? += fee;
+    } else {
+        // SPL token: CPI transfer with PDA as signer
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let winner_ta = ctx
+            .accounts
+            .winner_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        // Payout to winner
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_prog.to_account_info(),
+                TransferChecked {
+                    from: escrow_ta.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: winner_ta.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            payout,
+            mint.decimals,
+        )?;
+
+        // Fee to treasury. Skipped for zero-rake escrows: a zero-amount
+        // CPI wastes compute and some token programs reject it.
+        if fee > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: treasury_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                fee,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    profile!("settle_multi", "transferred");
+
+    // ---------------------------------------------------------------
+    // Mark settled (mutable borrow after all CPI).
+    // The `close = authority` constraint reclaims rent after handler.
+    // ---------------------------------------------------------------
+    ctx.accounts.escrow.settled = true;
+
+    emit!(MultiEscrowSettled {
+        lobby_id_hash,
+        winner,
+        payout,
+        fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,443 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION, RESULT_LOG_SEED};
+use crate::error::EscrowError;
+use crate::events::{EscrowSettlePending, FeeBurned, ReferralPaid, WinningsClaimed};
+use crate::instructions::result_log::log_result;
+use crate::instructions::settle::close_escrow_ata_if_empty;
+use crate::state::{EscrowStatus, MatchEscrow, ProgramConfig, ResultLog};
+
+/// Pull-style settlement, step one. The settle authority names the winner
+/// and collects the fee, but the payout stays in the escrow until the winner
+/// signs `claim_winnings`. The settler never needs the winner's token
+/// account, so winners without an ATA yet can create one and then claim.
+#[derive(Accounts)]
+pub struct SettlePending<'info> {
+    pub settle_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = settle_authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated against the escrow's fee destination in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Treasury's token account (only needed for SPL settlements with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Validated against escrow.referrer in handler. Only needed for
+    /// native settlements of referred escrows.
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// Referrer's token account (only needed for SPL settlements of referred escrows).
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked` and writable for
+    /// fee burns. Only needed for SPL settlements.
+    #[account(mut, address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL settlements.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Program config; only needed when the escrow uses the global treasury.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
+    /// Treasury's result log; when passed, the payout is recorded in it.
+    #[account(
+        mut,
+        seeds = [RESULT_LOG_SEED, result_log.treasury.as_ref()],
+        bump = result_log.bump,
+    )]
+    pub result_log: Option<Box<Account<'info, ResultLog>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pull-style settlement, step two. The recorded winner withdraws the
+/// payout, and the escrow closes to its rent payer unless `retain_record`.
+/// Also collects payouts that `settle` held back because the winner had
+/// no token account.
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// Winner's token account (only needed for SPL claims).
+    #[account(mut)]
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL claims).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL claims.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL claims.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn settle_pending_handler(ctx: Context<SettlePending>, winner: Pubkey) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // Avoids E0502 when we need &mut ctx.accounts.escrow later.
+    // ---------------------------------------------------------------
+    let escrow = &ctx.accounts.escrow;
+    let winner_is_player = escrow.is_player(&winner);
+    let both_funded = escrow.both_funded();
+    let winner_rounds = if winner == escrow.host {
+        escrow.host_wins
+    } else {
+        escrow.opponent_wins
+    };
+    let rounds_to_win = escrow.rounds_to_win;
+    let disputed = escrow.disputed;
+    let settled = escrow.settled;
+    let version = escrow.version;
+    let (total_pot, fee, payout) = escrow.compute_distribution()?;
+    let (referral, burn, treasury_fee) = escrow.split_fee(total_pot, fee)?;
+    let referrer_key = escrow.referrer;
+    let token_mint = escrow.token_mint;
+    let is_native = escrow.is_native_sol();
+    let treasury_key = if escrow.use_global_treasury {
+        ctx
+            .accounts
+            .config
+            .as_ref()
+            .ok_or(EscrowError::MissingConfig)?
+            .treasury
+    } else {
+        escrow.treasury
+    };
+    let lobby_id_hash = escrow.lobby_id_hash;
+    let bump = escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation (same rules as `settle`)
+    // ---------------------------------------------------------------
+    require!(winner_is_player, EscrowError::InvalidWinner);
+    require!(both_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(!disputed, EscrowError::Disputed);
+    let clock = Clock::get()?;
+    escrow.check_challenge(&winner, &clock)?;
+    escrow.check_min_duration(&clock)?;
+    require!(version == ESCROW_VERSION, EscrowError::UnsupportedVersion);
+    if rounds_to_win > 1 {
+        require!(
+            winner_rounds >= rounds_to_win,
+            EscrowError::SeriesNotDecided
+        );
+    }
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+
+    // Persist the in-progress flag before any CPI (see `settle`)
+    ctx.accounts.escrow.begin_settling()?;
+    ctx.accounts.escrow.exit(&crate::ID)?;
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Collect the fee; the payout stays put for `claim_winnings`
+    // ---------------------------------------------------------------
+    if is_native {
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= treasury_fee;
+        **treasury_info.try_borrow_mut_lamports()? += treasury_fee;
+
+        if referral > 0 {
+            let referrer = ctx
+                .accounts
+                .referrer
+                .as_ref()
+                .ok_or(EscrowError::InvalidReferrer)?;
+            require!(referrer.key() == referrer_key, EscrowError::InvalidReferrer);
+
+            **escrow_info.try_borrow_mut_lamports()? -= referral;
+            **referrer.try_borrow_mut_lamports()? += referral;
+        }
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        if treasury_fee > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                treasury_ta.owner == treasury_key,
+                EscrowError::NotAuthorized
+            );
+            require!(treasury_ta.mint == token_mint, EscrowError::WrongMint);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: treasury_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                treasury_fee,
+                mint.decimals,
+            )?;
+        }
+
+        if referral > 0 {
+            let referrer_ta = ctx
+                .accounts
+                .referrer_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                referrer_ta.owner == referrer_key,
+                EscrowError::InvalidReferrer
+            );
+            require!(referrer_ta.mint == token_mint, EscrowError::WrongMint);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: referrer_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                referral,
+                mint.decimals,
+            )?;
+        }
+
+        // Deflationary share of the fee, destroyed rather than paid out
+        if burn > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    Burn {
+                        mint: mint.to_account_info(),
+                        from: escrow_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                burn,
+            )?;
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Record the outcome (mutable borrow after all CPI). The escrow stays
+    // open until the winner claims.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Settled)?;
+    escrow.settling = false;
+    escrow.settled_at = Clock::get()?.unix_timestamp;
+    escrow.winner = winner;
+    escrow.pending_payout = payout;
+    log_result(
+        ctx.accounts.result_log.as_deref_mut(),
+        treasury_key,
+        lobby_id_hash,
+        winner,
+        payout,
+    )?;
+
+    emit!(EscrowSettlePending {
+        lobby_id_hash,
+        winner,
+        payout,
+        fee,
+    });
+    if referral > 0 {
+        emit!(ReferralPaid {
+            lobby_id_hash,
+            referrer: referrer_key,
+            amount: referral,
+        });
+    }
+    if burn > 0 {
+        emit!(FeeBurned {
+            lobby_id_hash,
+            amount: burn,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn claim_handler(ctx: Context<ClaimWinnings>) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let winner = ctx.accounts.escrow.winner;
+    let settled = ctx.accounts.escrow.settled;
+    let amount = ctx.accounts.escrow.pending_payout;
+    let claimed = ctx.accounts.escrow.payout_claimed;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(!claimed, EscrowError::AlreadyClaimed);
+    require!(settled && amount > 0, EscrowError::NothingToClaim);
+    require!(
+        ctx.accounts.winner.key() == winner,
+        EscrowError::NotWinner
+    );
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Pay out
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports go to the rent payer when the escrow closes.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let winner_info = ctx.accounts.winner.to_account_info();
+
+        **escrow_info.try_borrow_mut_lamports()? -= amount;
+        **winner_info.try_borrow_mut_lamports()? += amount;
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let winner_ta = ctx
+            .accounts
+            .winner_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(winner_ta.mint == token_mint, EscrowError::WrongMint);
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_prog.to_account_info(),
+                TransferChecked {
+                    from: escrow_ta.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: winner_ta.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            mint.decimals,
+        )?;
+
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        close_escrow_ata_if_empty(
+            escrow_ta,
+            token_prog,
+            ctx.accounts.escrow.to_account_info(),
+            ctx.accounts.rent_payer.to_account_info(),
+            signer_seeds,
+        )?;
+    }
+
+    // ---------------------------------------------------------------
+    // Clear the claim (mutable borrow after all CPI), then close
+    // unless the escrow opted to stay readable until `sweep_closed`.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.pending_payout = 0;
+    escrow.payout_claimed = true;
+    if !escrow.retain_record {
+        escrow.close(ctx.accounts.rent_payer.to_account_info())?;
+    }
+
+    emit!(WinningsClaimed {
+        lobby_id_hash,
+        winner,
+        amount,
+    });
+
+    Ok(())
+}
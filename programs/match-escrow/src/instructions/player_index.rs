@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use crate::constants::PLAYER_INDEX_SEED;
+use crate::error::EscrowError;
+use crate::events::{PlayerIndexAdded, PlayerIndexRemoved};
+use crate::state::PlayerIndex;
+
+/// Creates page `page` of a wallet's `PlayerIndex`. Permissionless: the
+/// index only lists what that wallet's own deposits add to it.
+#[derive(Accounts)]
+#[instruction(page: u8)]
+pub struct InitializePlayerIndex<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any wallet may have an index; only its key is stored.
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PlayerIndex::INIT_SPACE,
+        seeds = [PLAYER_INDEX_SEED, player.key().as_ref(), &[page]],
+        bump,
+    )]
+    pub player_index: Box<Account<'info, PlayerIndex>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_handler(ctx: Context<InitializePlayerIndex>, page: u8) -> Result<()> {
+    let index = &mut ctx.accounts.player_index;
+    index.player = ctx.accounts.player.key();
+    index.page = page;
+    index.lobbies = Vec::new();
+    index.bump = ctx.bumps.player_index;
+    Ok(())
+}
+
+/// Lets a player drop a lobby from their own index, e.g. one that was
+/// refunded or settled without the index being passed.
+#[derive(Accounts)]
+pub struct RemoveFromPlayerIndex<'info> {
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLAYER_INDEX_SEED, player.key().as_ref(), &[player_index.page]],
+        bump = player_index.bump,
+        has_one = player @ EscrowError::WrongPlayerIndex,
+    )]
+    pub player_index: Box<Account<'info, PlayerIndex>>,
+}
+
+pub fn remove_handler(ctx: Context<RemoveFromPlayerIndex>, lobby_id_hash: [u8; 32]) -> Result<()> {
+    let index = &mut ctx.accounts.player_index;
+    if index.remove(&lobby_id_hash) {
+        emit!(PlayerIndexRemoved {
+            player: index.player,
+            lobby_id_hash,
+        });
+    }
+    Ok(())
+}
+
+/// Lists `lobby_id_hash` in `index` when the depositor passed one.
+pub(crate) fn index_add(
+    index: Option<&mut Account<PlayerIndex>>,
+    player: Pubkey,
+    lobby_id_hash: [u8; 32],
+) -> Result<()> {
+    let Some(index) = index else {
+        return Ok(());
+    };
+    require!(index.player == player, EscrowError::WrongPlayerIndex);
+    index.add(lobby_id_hash)?;
+
+    emit!(PlayerIndexAdded {
+        player,
+        lobby_id_hash,
+    });
+
+    Ok(())
+}
+
+/// Drops a finished lobby from each index the settler passed. Each index
+/// must belong to one of the escrow's players.
+pub(crate) fn index_remove(
+    indexes: [Option<&mut Account<PlayerIndex>>; 2],
+    players: [Pubkey; 2],
+    lobby_id_hash: [u8; 32],
+) -> Result<()> {
+    for index in indexes.into_iter().flatten() {
+        require!(
+            players.contains(&index.player),
+            EscrowError::WrongPlayerIndex
+        );
+        if index.remove(&lobby_id_hash) {
+            emit!(PlayerIndexRemoved {
+                player: index.player,
+                lobby_id_hash,
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::events::SettlementQuote;
+use crate::state::MatchEscrow;
+
+/// Read-only quote of what `settle` would pay out for this escrow, so
+/// clients don't re-implement the fee math. Results are emitted as a
+/// `SettlementQuote` event (and logged) since Anchor handlers can't return
+/// values to off-chain callers directly; simulate the transaction to read it.
+#[derive(Accounts)]
+pub struct QuoteSettlement<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<QuoteSettlement>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+
+    let (total_pot, fee, payout) = escrow.compute_distribution()?;
+
+    msg!("quote: total_pot={} fee={} payout={}", total_pot, fee, payout);
+
+    emit!(SettlementQuote {
+        lobby_id_hash: escrow.lobby_id_hash,
+        total_pot,
+        fee,
+        payout,
+    });
+
+    Ok(())
+}
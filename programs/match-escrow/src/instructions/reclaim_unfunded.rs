@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::EscrowReclaimed;
+use crate::state::{EscrowStatus, MatchEscrow};
+
+/// Permissionless refund of a half-funded escrow once the deposit deadline
+/// has passed. Returns the lone deposit to whichever player made it and
+/// closes the escrow, reclaiming rent to the authority. For SPL escrows the
+/// emptied escrow token account is closed as well.
+#[derive(Accounts)]
+pub struct ReclaimUnfunded<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: Validated as the player who deposited in handler.
+    #[account(mut)]
+    pub depositor: UncheckedAccount<'info>,
+
+    /// Depositor's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub depositor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL refunds.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL refunds.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ReclaimUnfunded>) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    // A seat part-way through installments has a stake to return too
+    let host_deposited =
+        ctx.accounts.escrow.host_deposited || ctx.accounts.escrow.host_deposited_amount > 0;
+    let opponent_deposited = ctx.accounts.escrow.opponent_deposited
+        || ctx.accounts.escrow.opponent_deposited_amount > 0;
+    let settled = ctx.accounts.escrow.settled;
+    let host_wager = ctx.accounts.escrow.host_deposited_amount;
+    let opponent_wager = ctx.accounts.escrow.opponent_deposited_amount;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let deposit_window_open = ctx.accounts.escrow.deposit_window_open(&Clock::get()?);
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(!deposit_window_open, EscrowError::DepositWindowOpen);
+    require!(
+        !(host_deposited && opponent_deposited),
+        EscrowError::AlreadyFunded
+    );
+    require!(
+        host_deposited || opponent_deposited,
+        EscrowError::NoDepositToRefund
+    );
+
+    // Exactly one side deposited; refund that player's own stake
+    let (depositor, refund) = if host_deposited {
+        (host, host_wager)
+    } else {
+        (opponent, opponent_wager)
+    };
+
+    require!(
+        ctx.accounts.depositor.key() == depositor,
+        EscrowError::NotAuthorized
+    );
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Refund the lone deposit
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports are reclaimed by `close = authority`.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let depositor_info = ctx.accounts.depositor.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= refund,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= refund;
+        **depositor_info.try_borrow_mut_lamports()? += refund;
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let depositor_ta = ctx
+            .accounts
+            .depositor_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        // Refunds must land with the depositor, in the escrow's mint
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &ctx.accounts.escrow.key(),
+                    &token_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+        require!(depositor_ta.owner == depositor, EscrowError::NotAuthorized);
+        require!(depositor_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.amount >= refund,
+            EscrowError::InsufficientFunds
+        );
+        // Stray tokens sent to the ATA must not block the refund; the
+        // account is only closed once it is empty.
+        let remaining = escrow_ta.amount - refund;
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_prog.to_account_info(),
+                TransferChecked {
+                    from: escrow_ta.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: depositor_ta.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            refund,
+            mint.decimals,
+        )?;
+
+        if remaining == 0 {
+            token_interface::close_account(CpiContext::new_with_signer(
+                token_prog.to_account_info(),
+                CloseAccount {
+                    account: escrow_ta.to_account_info(),
+                    destination: ctx.accounts.authority.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Clear the deposit flag (mutable borrow after all CPI).
+    // The `close = authority` constraint reclaims rent after handler.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.host_deposited = false;
+    escrow.opponent_deposited = false;
+    escrow.host_deposited_amount = 0;
+    escrow.opponent_deposited_amount = 0;
+    escrow.transition(EscrowStatus::Refunded)?;
+
+    emit!(EscrowReclaimed {
+        lobby_id_hash,
+        depositor,
+        amount: refund,
+    });
+
+    Ok(())
+}
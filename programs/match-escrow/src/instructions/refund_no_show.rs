@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{ESCROW_SEED, MAX_FEE_BPS};
+use crate::error::EscrowError;
+use crate::events::NoShowRefunded;
+use crate::state::{EscrowStatus, MatchEscrow};
+
+/// Authority-only resolution when exactly one player funded and the other
+/// is declared a no-show. Returns the lone deposit to whoever made it, minus
+/// an optional no-show fee to the treasury, and closes the escrow.
+#[derive(Accounts)]
+pub struct RefundNoShow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated as the player who deposited in handler.
+    #[account(mut)]
+    pub depositor: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against escrow.treasury in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Depositor's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub depositor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account (only needed for SPL refunds with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL refunds.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL refunds.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RefundNoShow>, no_show_fee_bps: u16) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    // A seat part-way through installments has a stake to return too
+    let host_deposited =
+        ctx.accounts.escrow.host_deposited || ctx.accounts.escrow.host_deposited_amount > 0;
+    let opponent_deposited = ctx.accounts.escrow.opponent_deposited
+        || ctx.accounts.escrow.opponent_deposited_amount > 0;
+    let settled = ctx.accounts.escrow.settled;
+    let host_wager = ctx.accounts.escrow.host_deposited_amount;
+    let opponent_wager = ctx.accounts.escrow.opponent_deposited_amount;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let escrow_key = ctx.accounts.escrow.key();
+    let treasury_key = ctx.accounts.escrow.treasury;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(no_show_fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+    require!(
+        !(host_deposited && opponent_deposited),
+        EscrowError::AlreadyFunded
+    );
+    require!(
+        host_deposited || opponent_deposited,
+        EscrowError::NoDepositToRefund
+    );
+
+    // The player who showed up gets their own stake back
+    let (depositor, stake) = if host_deposited {
+        (host, host_wager)
+    } else {
+        (opponent, opponent_wager)
+    };
+
+    require!(
+        ctx.accounts.depositor.key() == depositor,
+        EscrowError::NotAuthorized
+    );
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+
+    let fee = (stake as u128)
+        .checked_mul(no_show_fee_bps as u128)
+        .ok_or(EscrowError::InsufficientFunds)?
+        .checked_div(10_000)
+        .ok_or(EscrowError::InsufficientFunds)? as u64;
+    let refund = stake
+        .checked_sub(fee)
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Refund the lone deposit
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports are reclaimed by `close = authority`.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let depositor_info = ctx.accounts.depositor.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= stake,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= refund;
+        **depositor_info.try_borrow_mut_lamports()? += refund;
+
+        **escrow_info.try_borrow_mut_lamports()? -= fee;
+        **treasury_info.try_borrow_mut_lamports()? += fee;
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let depositor_ta = ctx
+            .accounts
+            .depositor_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        // Refunds must land with the depositor, in the escrow's mint
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &escrow_key,
+                    &token_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+        require!(depositor_ta.owner == depositor, EscrowError::NotAuthorized);
+        require!(depositor_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.amount >= stake,
+            EscrowError::InsufficientFunds
+        );
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_prog.to_account_info(),
+                TransferChecked {
+                    from: escrow_ta.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: depositor_ta.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            refund,
+            mint.decimals,
+        )?;
+
+        if fee > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                treasury_ta.owner == treasury_key,
+                EscrowError::NotAuthorized
+            );
+            require!(treasury_ta.mint == token_mint, EscrowError::WrongMint);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: treasury_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                fee,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Clear the deposit flag (mutable borrow after all CPI).
+    // The `close = authority` constraint reclaims rent after handler.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.host_deposited = false;
+    escrow.opponent_deposited = false;
+    escrow.host_deposited_amount = 0;
+    escrow.opponent_deposited_amount = 0;
+    escrow.transition(EscrowStatus::Refunded)?;
+
+    emit!(NoShowRefunded {
+        lobby_id_hash,
+        depositor,
+        refund,
+        fee,
+    });
+
+    Ok(())
+}
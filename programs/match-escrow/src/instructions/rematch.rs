@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+use crate::constants::{CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION, MAX_ESCROW_LIFETIME_SECS};
+use crate::error::EscrowError;
+use crate::events::EscrowInitialized;
+use crate::state::{DepositMethod, EscrowStatus, MatchEscrow, ProgramConfig};
+
+/// Authority-only shortcut for an immediate rematch: opens a fresh escrow
+/// under `new_lobby_id_hash` with the players, stakes, mint, treasury and
+/// fee of a settled escrow. Only fresh unix timestamp deadlines are taken.
+///
+/// The source must still exist, so it has to have been created with
+/// `retain_record` (and not yet swept).
+#[derive(Accounts)]
+#[instruction(new_lobby_id_hash: [u8; 32])]
+pub struct Rematch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ESCROW_SEED, source.lobby_id_hash.as_ref()],
+        bump = source.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+    )]
+    pub source: Account<'info, MatchEscrow>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MatchEscrow::INIT_SPACE,
+        seeds = [ESCROW_SEED, new_lobby_id_hash.as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<Rematch>,
+    new_lobby_id_hash: [u8; 32],
+    deposit_deadline: i64,
+    match_deadline: i64,
+    settlement_deadline: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+    require!(ctx.accounts.source.settled, EscrowError::NotSettled);
+
+    let source = &ctx.accounts.source;
+    let host = source.host;
+    let opponent = source.opponent;
+    let host_wager = source.host_wager;
+    let opponent_wager = source.opponent_wager;
+    let token_mint = source.token_mint;
+    let treasury = source.treasury;
+    let fee_bps = source.fee_bps;
+    let rounds_to_win = source.rounds_to_win;
+    let free_entry = source.free_entry;
+    let retain_record = source.retain_record;
+    let use_global_treasury = source.use_global_treasury;
+    let require_authorized_deposit = source.require_authorized_deposit;
+    let oracle = source.oracle;
+    let referrer = source.referrer;
+    let referral_bps = source.referral_bps;
+    let rounding = source.rounding;
+    let required_collection = source.required_collection;
+    let settle_authority = source.settle_authority;
+    let burn_bps = source.burn_bps;
+    let min_fee = source.min_fee;
+    let allow_player_treasury = source.allow_player_treasury;
+    let max_fee = source.max_fee;
+    let min_match_duration = source.min_match_duration;
+    let crank_reward_bps = source.crank_reward_bps;
+    let sponsored = source.sponsored;
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.lobby_id_hash = new_lobby_id_hash;
+    escrow.host = host;
+    escrow.opponent = opponent;
+    escrow.host_wager = host_wager;
+    escrow.opponent_wager = opponent_wager;
+    escrow.token_mint = token_mint;
+    escrow.treasury = treasury;
+    escrow.authority = ctx.accounts.authority.key();
+    escrow.host_deposited = false;
+    escrow.opponent_deposited = false;
+    escrow.host_deposited_amount = 0;
+    escrow.opponent_deposited_amount = 0;
+    escrow.host_deposit_method = DepositMethod::None as u8;
+    escrow.opponent_deposit_method = DepositMethod::None as u8;
+    escrow.pending_payout = 0;
+    escrow.payout_claimed = false;
+    escrow.first_depositor = Pubkey::default();
+    escrow.last_confirm_nonce = 0;
+    escrow.funded_at = 0;
+    escrow.bonus_pool = 0;
+    escrow.settled = false;
+    escrow.bump = ctx.bumps.escrow;
+    escrow.deposit_deadline = deposit_deadline;
+    escrow.fee_bps = fee_bps;
+    escrow.match_deadline = match_deadline;
+    escrow.rounds_to_win = rounds_to_win;
+    escrow.host_wins = 0;
+    escrow.opponent_wins = 0;
+    escrow.disputed = false;
+    escrow.result_commitment = [0u8; 32];
+    escrow.result_committer = Pubkey::default();
+    escrow.reveal_deadline = 0;
+    escrow.free_entry = free_entry;
+    escrow.settled_at = 0;
+    escrow.winner = Pubkey::default();
+    escrow.retain_record = retain_record;
+    escrow.host_payment_ref = [0u8; 32];
+    escrow.opponent_payment_ref = [0u8; 32];
+    escrow.slot_deadlines = false;
+    escrow.deposit_deadline_slot = 0;
+    escrow.match_deadline_slot = 0;
+    escrow.use_global_treasury = use_global_treasury;
+    escrow.require_authorized_deposit = require_authorized_deposit;
+    escrow.rent_payer = ctx.accounts.authority.key();
+    escrow.match_ref = [0u8; 32];
+    escrow.version = ESCROW_VERSION;
+    escrow.settling = false;
+    escrow.settlement_deadline = settlement_deadline;
+    escrow.expires_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(MAX_ESCROW_LIFETIME_SECS)
+        .ok_or(EscrowError::NotYetExpired)?;
+    escrow.oracle = oracle;
+    escrow.referrer = referrer;
+    escrow.referral_bps = referral_bps;
+    escrow.rounding = rounding;
+    escrow.required_collection = required_collection;
+    escrow.proposed_winner = Pubkey::default();
+    escrow.challenge_deadline = 0;
+    escrow.settle_authority = settle_authority;
+    escrow.burn_bps = burn_bps;
+    escrow.min_fee = min_fee;
+    escrow.allow_player_treasury = allow_player_treasury;
+    escrow.max_fee = max_fee;
+    escrow.min_match_duration = min_match_duration;
+    // A default winner is a per-match commitment; the rematch starts without one
+    escrow.default_winner = Pubkey::default();
+    escrow.crank_reward_bps = crank_reward_bps;
+    // Both seats are filled from the source match, so there is no join to approve
+    escrow.require_join_approval = false;
+    escrow.sponsored = sponsored;
+    escrow.status = EscrowStatus::Created as u8;
+
+    emit!(EscrowInitialized {
+        lobby_id_hash: new_lobby_id_hash,
+        escrow: escrow.key(),
+        bump: escrow.bump,
+        host,
+        opponent,
+        host_wager,
+        opponent_wager,
+        token_mint,
+        treasury,
+        authority: escrow.authority,
+        deposit_deadline,
+        fee_bps,
+        match_deadline,
+        rounds_to_win,
+        free_entry,
+        retain_record,
+        slot_deadlines: false,
+        deposit_deadline_slot: 0,
+        match_deadline_slot: 0,
+        use_global_treasury,
+        require_authorized_deposit,
+        rent_payer: escrow.rent_payer,
+        match_ref: [0u8; 32],
+        settlement_deadline,
+        oracle,
+        referrer,
+        referral_bps,
+        rounding,
+        required_collection,
+        settle_authority,
+        burn_bps,
+        min_fee,
+        max_fee,
+        min_match_duration,
+        default_winner: Pubkey::default(),
+        crank_reward_bps,
+        require_join_approval: false,
+        sponsored,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::RoundReported;
+use crate::state::MatchEscrow;
+
+/// Authority-only record of a single game result within a best-of-N series.
+/// `settle` only pays out once one side reaches `rounds_to_win`.
+#[derive(Accounts)]
+pub struct ReportRound<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<ReportRound>, round_winner: Pubkey) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(
+        escrow.host_deposited && escrow.opponent_deposited,
+        EscrowError::EscrowNotFunded
+    );
+    require!(
+        escrow.host_wins < escrow.rounds_to_win && escrow.opponent_wins < escrow.rounds_to_win,
+        EscrowError::SeriesComplete
+    );
+
+    if round_winner == escrow.host {
+        escrow.host_wins += 1;
+    } else if round_winner == escrow.opponent {
+        escrow.opponent_wins += 1;
+    } else {
+        return err!(EscrowError::InvalidWinner);
+    }
+
+    emit!(RoundReported {
+        lobby_id_hash: escrow.lobby_id_hash,
+        round_winner,
+        host_wins: escrow.host_wins,
+        opponent_wins: escrow.opponent_wins,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::state::MatchEscrow;
+
+/// Read-only guard that succeeds only while both players have deposited and
+/// the escrow is unsettled. Clients compose it ahead of match-start
+/// instructions, and other programs can CPI into it, to gate on funding
+/// with a single check.
+#[derive(Accounts)]
+pub struct RequireFunded<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<RequireFunded>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+
+    require!(
+        escrow.both_funded() && !escrow.settled,
+        EscrowError::NotFunded
+    );
+
+    Ok(())
+}
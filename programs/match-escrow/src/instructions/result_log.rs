@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::constants::RESULT_LOG_SEED;
+use crate::error::EscrowError;
+use crate::events::ResultLogged;
+use crate::state::{ResultEntry, ResultLog};
+
+/// Creates the `ResultLog` for a treasury. Permissionless: the log only
+/// ever records what settlements write to it, so anyone may pay its rent.
+#[derive(Accounts)]
+pub struct InitializeResultLog<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any fee destination may have a log; only its key is stored.
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ResultLog::INIT_SPACE,
+        seeds = [RESULT_LOG_SEED, treasury.key().as_ref()],
+        bump,
+    )]
+    pub result_log: Box<Account<'info, ResultLog>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_handler(ctx: Context<InitializeResultLog>) -> Result<()> {
+    let log = &mut ctx.accounts.result_log;
+    log.treasury = ctx.accounts.treasury.key();
+    log.count = 0;
+    log.bump = ctx.bumps.result_log;
+    Ok(())
+}
+
+/// Appends a payout to `log` when the settler passed one. Settlement paths
+/// take the log as an optional account so existing clients keep working.
+pub(crate) fn log_result(
+    log: Option<&mut Account<ResultLog>>,
+    treasury: Pubkey,
+    lobby_id_hash: [u8; 32],
+    winner: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let Some(log) = log else {
+        return Ok(());
+    };
+    require!(log.treasury == treasury, EscrowError::InvalidResultLog);
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let sequence = log.record(ResultEntry {
+        lobby_id_hash,
+        winner,
+        amount,
+        timestamp,
+    });
+
+    emit!(ResultLogged {
+        treasury,
+        lobby_id_hash,
+        winner,
+        amount,
+        timestamp,
+        sequence,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,361 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::constants::{
+    CONFIG_SEED, FEE_BPS, MAX_FEE_BPS, MAX_SERIES_ROUNDS, MAX_WAGER_LAMPORTS, SERIES_ESCROW_SEED,
+};
+use crate::error::EscrowError;
+use crate::events::{
+    SeriesDepositMade, SeriesEscrowInitialized, SeriesEscrowSettled, SeriesRoundReported,
+};
+use crate::state::{ProgramConfig, SeriesEscrow};
+
+/// Arguments for `initialize_series_escrow`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeSeriesEscrowParams {
+    /// SHA-256 hash of the Convex lobby ID string (PDA seed)
+    pub lobby_id_hash: [u8; 32],
+    pub host: Pubkey,
+    pub opponent: Pubkey,
+    /// Per-player stake of each round in lamports, 1 to `MAX_SERIES_ROUNDS`
+    /// rounds. Each player deposits the sum.
+    pub round_wagers: Vec<u64>,
+    pub treasury: Pubkey,
+    pub deposit_deadline: i64,
+    /// Defaults to `FEE_BPS` when omitted
+    pub fee_bps: Option<u16>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: InitializeSeriesEscrowParams)]
+pub struct InitializeSeriesEscrow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SeriesEscrow::INIT_SPACE,
+        seeds = [SERIES_ESCROW_SEED, params.lobby_id_hash.as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, SeriesEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves a player's stake for the whole series into the escrow.
+#[derive(Accounts)]
+pub struct DepositSeries<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SERIES_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, SeriesEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only record of one round's winner. Rounds are reported in
+/// play order, so `round` must be the next unreported index.
+#[derive(Accounts)]
+pub struct ReportSeriesRound<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SERIES_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, SeriesEscrow>,
+}
+
+/// Authority-only payout once every round is reported: each player gets
+/// their tally minus `fee_bps`, and the escrow closes to the authority.
+#[derive(Accounts)]
+pub struct SettleSeries<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SERIES_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        has_one = host @ EscrowError::NotAuthorized,
+        has_one = opponent @ EscrowError::NotAuthorized,
+        has_one = treasury @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, SeriesEscrow>,
+
+    /// CHECK: Validated by has_one; receives the host's tally.
+    #[account(mut)]
+    pub host: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; receives the opponent's tally.
+    #[account(mut)]
+    pub opponent: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; receives the fee.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+pub fn initialize_handler(
+    ctx: Context<InitializeSeriesEscrow>,
+    params: InitializeSeriesEscrowParams,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    let fee_bps = params.fee_bps.unwrap_or(FEE_BPS);
+    require!(fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+    require!(params.host != params.opponent, EscrowError::DuplicatePlayer);
+    require!(
+        params.host != Pubkey::default() && params.opponent != Pubkey::default(),
+        EscrowError::DuplicatePlayer
+    );
+    require!(
+        (1..=MAX_SERIES_ROUNDS).contains(&params.round_wagers.len()),
+        EscrowError::InvalidRoundsToWin
+    );
+    require!(
+        params.round_wagers.iter().all(|wager| *wager > 0),
+        EscrowError::ZeroWager
+    );
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.lobby_id_hash = params.lobby_id_hash;
+    escrow.host = params.host;
+    escrow.opponent = params.opponent;
+    escrow.treasury = params.treasury;
+    escrow.authority = ctx.accounts.authority.key();
+    escrow.round_wagers = params.round_wagers.clone();
+    escrow.round_results = Vec::new();
+    escrow.host_tally = 0;
+    escrow.opponent_tally = 0;
+    escrow.host_deposited = false;
+    escrow.opponent_deposited = false;
+    escrow.settled = false;
+    escrow.bump = ctx.bumps.escrow;
+    escrow.deposit_deadline = params.deposit_deadline;
+    escrow.fee_bps = fee_bps;
+
+    // The whole series is one deposit, so the per-match cap applies to it
+    require!(
+        escrow.stake_per_player()? <= MAX_WAGER_LAMPORTS,
+        EscrowError::WagerTooLarge
+    );
+
+    emit!(SeriesEscrowInitialized {
+        lobby_id_hash: params.lobby_id_hash,
+        host: params.host,
+        opponent: params.opponent,
+        round_wagers: params.round_wagers,
+        treasury: params.treasury,
+        authority: escrow.authority,
+        deposit_deadline: params.deposit_deadline,
+        fee_bps,
+    });
+
+    Ok(())
+}
+
+pub fn deposit_handler(ctx: Context<DepositSeries>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    // ---------------------------------------------------------------
+    // Extract all needed values BEFORE any CPI calls (avoids E0502).
+    // ---------------------------------------------------------------
+    let depositor_key = ctx.accounts.depositor.key();
+    let is_host = depositor_key == ctx.accounts.escrow.host;
+    let is_opponent = depositor_key == ctx.accounts.escrow.opponent;
+    let already_deposited = if is_host {
+        ctx.accounts.escrow.host_deposited
+    } else {
+        ctx.accounts.escrow.opponent_deposited
+    };
+    let settled = ctx.accounts.escrow.settled;
+    let deposit_deadline = ctx.accounts.escrow.deposit_deadline;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let amount = ctx.accounts.escrow.stake_per_player()?;
+
+    require!(is_host || is_opponent, EscrowError::NotAuthorized);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        Clock::get()?.unix_timestamp <= deposit_deadline,
+        EscrowError::DepositWindowClosed
+    );
+    require!(!already_deposited, EscrowError::AlreadyDeposited);
+
+    // ---------------------------------------------------------------
+    // Transfer funds into the escrow
+    // ---------------------------------------------------------------
+    let balance_before = ctx.accounts.escrow.to_account_info().lamports();
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.depositor.to_account_info(),
+            to: ctx.accounts.escrow.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_ctx, amount)?;
+
+    let received = ctx
+        .accounts
+        .escrow
+        .to_account_info()
+        .lamports()
+        .checked_sub(balance_before)
+        .ok_or(EscrowError::DepositShortfall)?;
+    require!(received == amount, EscrowError::DepositShortfall);
+
+    // ---------------------------------------------------------------
+    // Mark deposit flag (mutable borrow begins here, after all CPI)
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    if is_host {
+        escrow.host_deposited = true;
+    } else {
+        escrow.opponent_deposited = true;
+    }
+
+    emit!(SeriesDepositMade {
+        lobby_id_hash,
+        depositor: depositor_key,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Credits round `round`'s stake from both players to `round_winner`. Only
+/// the tallies change; no lamports move until `settle_series`.
+pub fn report_round_handler(
+    ctx: Context<ReportSeriesRound>,
+    round: u8,
+    round_winner: Pubkey,
+) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(escrow.both_funded(), EscrowError::EscrowNotFunded);
+    require!(!escrow.all_rounds_played(), EscrowError::SeriesComplete);
+    require!(
+        round as usize == escrow.round_results.len(),
+        EscrowError::InvalidRound
+    );
+
+    let pot = escrow.round_wagers[round as usize]
+        .checked_mul(2)
+        .ok_or(EscrowError::InsufficientFunds)?;
+    if round_winner == escrow.host {
+        escrow.host_tally = escrow
+            .host_tally
+            .checked_add(pot)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        escrow.round_results.push(1);
+    } else if round_winner == escrow.opponent {
+        escrow.opponent_tally = escrow
+            .opponent_tally
+            .checked_add(pot)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        escrow.round_results.push(2);
+    } else {
+        return err!(EscrowError::InvalidWinner);
+    }
+
+    emit!(SeriesRoundReported {
+        lobby_id_hash: escrow.lobby_id_hash,
+        round,
+        round_winner,
+        pot,
+        host_tally: escrow.host_tally,
+        opponent_tally: escrow.opponent_tally,
+    });
+
+    Ok(())
+}
+
+/// Pays out both tallies. The fee is taken from each tally separately (so
+/// a player who won nothing pays nothing) and rounded down.
+pub fn settle_handler(ctx: Context<SettleSeries>) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let settled = ctx.accounts.escrow.settled;
+    let all_rounds_played = ctx.accounts.escrow.all_rounds_played();
+    let host_tally = ctx.accounts.escrow.host_tally;
+    let opponent_tally = ctx.accounts.escrow.opponent_tally;
+    let fee_bps = ctx.accounts.escrow.fee_bps;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(all_rounds_played, EscrowError::SeriesIncomplete);
+
+    let fee_of = |tally: u64| -> Result<u64> {
+        Ok((tally as u128)
+            .checked_mul(fee_bps as u128)
+            .ok_or(EscrowError::InsufficientFunds)?
+            .checked_div(10_000)
+            .ok_or(EscrowError::InsufficientFunds)? as u64)
+    };
+    let host_fee = fee_of(host_tally)?;
+    let opponent_fee = fee_of(opponent_tally)?;
+    let fee = host_fee
+        .checked_add(opponent_fee)
+        .ok_or(EscrowError::InsufficientFunds)?;
+    let host_payout = host_tally - host_fee;
+    let opponent_payout = opponent_tally - opponent_fee;
+    let total_pot = host_tally
+        .checked_add(opponent_tally)
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    // ---------------------------------------------------------------
+    // Transfer funds. Remaining rent-exempt lamports are reclaimed by
+    // `close = authority`.
+    // ---------------------------------------------------------------
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    let host_info = ctx.accounts.host.to_account_info();
+    let opponent_info = ctx.accounts.opponent.to_account_info();
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+
+    require!(
+        escrow_info.lamports() >= total_pot,
+        EscrowError::InsufficientFunds
+    );
+
+    **escrow_info.try_borrow_mut_lamports()? -= host_payout;
+    **host_info.try_borrow_mut_lamports()? += host_payout;
+
+    **escrow_info.try_borrow_mut_lamports()? -= opponent_payout;
+    **opponent_info.try_borrow_mut_lamports()? += opponent_payout;
+
+    **escrow_info.try_borrow_mut_lamports()? -= fee;
+    **treasury_info.try_borrow_mut_lamports()? += fee;
+
+    ctx.accounts.escrow.settled = true;
+
+    emit!(SeriesEscrowSettled {
+        lobby_id_hash,
+        host_payout,
+        opponent_payout,
+        fee,
+    });
+
+    Ok(())
+}
@@ -1,116 +1,304 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token;
-use anchor_spl::token::{TokenAccount, Transfer as SplTransfer};
-use crate::constants::{ESCROW_SEED, FEE_BPS};
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::{
+    self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::constants::{
+    CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION, FEE_VAULT_SEED, PLAYER_INDEX_SEED, RESULT_LOG_SEED,
+};
 use crate::error::EscrowError;
-use crate::state::MatchEscrow;
+use crate::events::{EscrowClosed, EscrowSettlePending, EscrowSettled, FeeBurned, ReferralPaid};
+use crate::instructions::deposit::require_undelegated;
+use crate::instructions::fee_vault::{emit_accrual, fee_recipient};
+use crate::instructions::player_index::index_remove;
+use crate::instructions::result_log::log_result;
+use crate::state::{EscrowStatus, FeeVault, MatchEscrow, PlayerIndex, ProgramConfig, ResultLog};
 
+/// Accounts for paying out a single winner. Signed by the escrow's
+/// `settle_authority`; the fee goes to the treasury pinned by the escrow,
+/// so the settler cannot redirect it. Rent goes to `rent_payer` unless
+/// `settle` is told to gift it to the winner.
+///
+/// Racing settlements: when two settle/forfeit transactions land together,
+/// the first closes the escrow and the second fails Anchor's account checks
+/// with `AccountNotInitialized` (3012) before the handler runs, the same
+/// error as for an escrow that never existed. Clients tell the two apart by
+/// the `EscrowClosed` event (or the PDA's transaction history) for the
+/// lobby. Escrows created with `retain_record` act as a tombstone instead:
+/// the loser of the race gets a clean `AlreadySettled`.
+///
+/// Frozen token accounts: if the mint's freeze authority has frozen the
+/// escrow's, treasury's or referrer's token account, the payout fails with
+/// `TokenAccountFrozen`; thaw the account and retry. A frozen winner account
+/// instead leaves the payout pending for `claim_winnings`.
 #[derive(Accounts)]
 pub struct Settle<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    #[account(address = payout.escrow.settle_authority @ EscrowError::NotAuthorized)]
+    pub settle_authority: Signer<'info>,
+
+    pub payout: Payout<'info>,
+}
 
+/// Everything a single-winner payout moves funds between, without the
+/// signer: `Settle` adds the settle authority, `SettleSigned` the players.
+#[derive(Accounts)]
+pub struct Payout<'info> {
     #[account(
         mut,
         seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
         bump = escrow.bump,
-        has_one = authority @ EscrowError::NotAuthorized,
-        close = authority,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
     )]
     pub escrow: Account<'info, MatchEscrow>,
 
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
     /// CHECK: Validated against escrow.host or escrow.opponent in handler.
     #[account(mut)]
     pub winner: UncheckedAccount<'info>,
 
-    /// CHECK: Validated against escrow.treasury in handler.
+    /// CHECK: Validated against the escrow's fee destination in handler.
     #[account(mut)]
     pub treasury: UncheckedAccount<'info>,
 
-    /// Winner's token account (only needed for SPL settlements).
+    /// Winner's token account (only needed for SPL settlements). WSOL
+    /// escrows may omit it to pay the winner in unwrapped SOL instead;
+    /// other SPL escrows that omit it hold the payout for `claim_winnings`.
     #[account(mut)]
-    pub winner_token_account: Option<Account<'info, TokenAccount>>,
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Treasury's token account (only needed for SPL settlements).
+    /// Treasury's token account (only needed for SPL settlements with a fee).
     #[account(mut)]
-    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
     /// Escrow's token account (only needed for SPL settlements).
     #[account(mut)]
-    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked` and writable for
+    /// fee burns. Only needed for SPL settlements.
+    #[account(mut, address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
 
-    /// CHECK: Token program, validated by address constraint. Only needed for SPL settlements.
-    #[account(address = anchor_spl::token::ID)]
-    pub token_program: Option<UncheckedAccount<'info>>,
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL settlements.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Program config; only needed when the escrow uses the global treasury.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
+    /// CHECK: Validated against escrow.referrer in handler. Only needed for
+    /// native settlements of referred escrows.
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// Referrer's token account (only needed for SPL settlements of referred escrows).
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's result log; when passed, the payout is recorded in it.
+    #[account(
+        mut,
+        seeds = [RESULT_LOG_SEED, result_log.treasury.as_ref()],
+        bump = result_log.bump,
+    )]
+    pub result_log: Option<Box<Account<'info, ResultLog>>>,
+
+    /// Treasury's fee vault; when passed, the treasury fee accrues there for
+    /// `sweep_fees` instead of being paid out directly. SPL fees then go to
+    /// a `treasury_token_account` owned by the vault.
+    #[account(
+        mut,
+        seeds = [FEE_VAULT_SEED, fee_vault.treasury.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Option<Box<Account<'info, FeeVault>>>,
+
+    /// Players' `PlayerIndex` pages listing this lobby; whichever are
+    /// passed have it removed.
+    #[account(
+        mut,
+        seeds = [PLAYER_INDEX_SEED, host_index.player.as_ref(), &[host_index.page]],
+        bump = host_index.bump,
+    )]
+    pub host_index: Option<Box<Account<'info, PlayerIndex>>>,
+
+    #[account(
+        mut,
+        seeds = [PLAYER_INDEX_SEED, opponent_index.player.as_ref(), &[opponent_index.page]],
+        bump = opponent_index.bump,
+    )]
+    pub opponent_index: Option<Box<Account<'info, PlayerIndex>>>,
+
+    /// CHECK: Wallet the winner redirects their payout to (e.g. a charity
+    /// or team pool). Only honoured when `winner` signs; SPL payouts then go
+    /// to a `winner_token_account` owned by it.
+    #[account(mut)]
+    pub payout_recipient: Option<UncheckedAccount<'info>>,
 
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Settle>, winner: Pubkey) -> Result<()> {
+impl Payout<'_> {
+    /// Wallet `winner`'s payout lands with: `payout_recipient` when passed,
+    /// else the winner.
+    pub(crate) fn recipient(&self, winner: Pubkey) -> Pubkey {
+        self.payout_recipient
+            .as_ref()
+            .map_or(winner, |recipient| recipient.key())
+    }
+}
+
+/// With `rent_to_winner`, the reclaimed rent of the escrow (and its token
+/// account) goes to the winner as a bonus instead of the rent payer.
+pub fn handler(ctx: Context<Settle>, winner: Pubkey, rent_to_winner: bool) -> Result<()> {
+    // Reject outsiders up front, even when the authority signs, so a
+    // compromised server key can never route the pot to a third party.
+    let escrow = &ctx.accounts.payout.escrow;
+    require!(escrow.is_player(&winner), EscrowError::InvalidWinner);
+
+    // Best-of-N series only pay out once the winner has taken enough rounds.
+    // Single-game escrows (rounds_to_win == 1) settle without round reports.
+    if escrow.rounds_to_win > 1 {
+        let wins = if winner == escrow.host {
+            escrow.host_wins
+        } else {
+            escrow.opponent_wins
+        };
+        require!(
+            wins >= escrow.rounds_to_win,
+            EscrowError::SeriesNotDecided
+        );
+    }
+
+    let (payout, fee) = pay_winner(&mut ctx.accounts.payout, winner, rent_to_winner)?;
+
+    emit!(EscrowSettled {
+        lobby_id_hash: ctx.accounts.payout.escrow.lobby_id_hash,
+        winner,
+        payout,
+        fee,
+        recipient: ctx.accounts.payout.recipient(winner),
+    });
+
+    Ok(())
+}
+
+/// Validates `winner` and distributes the pot: `fee_bps` to the treasury,
+/// the remainder to the winner. Shared by every instruction that pays out
+/// a single winner through the `Payout` accounts. Rent reclaimed by closing
+/// accounts goes to the winner when `rent_to_winner`, else to the rent
+/// payer. Returns `(payout, fee)`.
+pub(crate) fn pay_winner(
+    accounts: &mut Payout,
+    winner: Pubkey,
+    rent_to_winner: bool,
+) -> Result<(u64, u64)> {
     // ---------------------------------------------------------------
     // Extract all values from escrow before any transfers.
-    // Avoids E0502 when we need &mut ctx.accounts.escrow later.
+    // Avoids E0502 when we need &mut accounts.escrow later.
     // ---------------------------------------------------------------
-    let host = ctx.accounts.escrow.host;
-    let opponent = ctx.accounts.escrow.opponent;
-    let host_deposited = ctx.accounts.escrow.host_deposited;
-    let opponent_deposited = ctx.accounts.escrow.opponent_deposited;
-    let settled = ctx.accounts.escrow.settled;
-    let wager_lamports = ctx.accounts.escrow.wager_lamports;
-    let is_native = ctx.accounts.escrow.is_native_sol();
-    let treasury_key = ctx.accounts.escrow.treasury;
-    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
-    let bump = ctx.accounts.escrow.bump;
+    let winner_is_player = accounts.escrow.is_player(&winner);
+    let both_funded = accounts.escrow.both_funded();
+    let disputed = accounts.escrow.disputed;
+    let settled = accounts.escrow.settled;
+    let (total_pot, fee, payout) = accounts.escrow.compute_distribution()?;
+    let (referral, burn, treasury_fee) = accounts.escrow.split_fee(total_pot, fee)?;
+    let referrer_key = accounts.escrow.referrer;
+    let token_mint = accounts.escrow.token_mint;
+    let is_native = accounts.escrow.is_native_sol();
+    let retain_record = accounts.escrow.retain_record;
+    let treasury_key = if accounts.escrow.use_global_treasury {
+        accounts
+            .config
+            .as_ref()
+            .ok_or(EscrowError::MissingConfig)?
+            .treasury
+    } else {
+        accounts.escrow.treasury
+    };
+    let lobby_id_hash = accounts.escrow.lobby_id_hash;
+    let bump = accounts.escrow.bump;
 
     // ---------------------------------------------------------------
     // Validation
     // ---------------------------------------------------------------
+    require!(winner_is_player, EscrowError::InvalidWinner);
+    require!(both_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(!disputed, EscrowError::Disputed);
+    let clock = Clock::get()?;
+    accounts.escrow.check_challenge(&winner, &clock)?;
+    accounts.escrow.check_min_duration(&clock)?;
     require!(
-        winner == host || winner == opponent,
-        EscrowError::InvalidWinner
-    );
-    require!(
-        host_deposited && opponent_deposited,
-        EscrowError::EscrowNotFunded
+        accounts.escrow.version == ESCROW_VERSION,
+        EscrowError::UnsupportedVersion
     );
-    require!(!settled, EscrowError::AlreadySettled);
     require!(
-        ctx.accounts.winner.key() == winner,
+        accounts.winner.key() == winner,
         EscrowError::InvalidWinner
     );
     require!(
-        ctx.accounts.treasury.key() == treasury_key,
+        accounts.treasury.key() == treasury_key,
         EscrowError::NotAuthorized
     );
+    // One account in both roles would take two sequential lamport
+    // credits; reject the misconfiguration instead of reasoning about it
+    require!(
+        accounts.winner.key() != accounts.treasury.key(),
+        EscrowError::WinnerIsTreasury
+    );
+    // Redirecting the payout takes the winner's own consent
+    let recipient = accounts.recipient(winner);
+    if recipient != winner {
+        require!(
+            accounts.winner.is_signer,
+            EscrowError::RecipientNotApproved
+        );
+        require!(recipient != treasury_key, EscrowError::WinnerIsTreasury);
+    }
+    let fee_to = fee_recipient(accounts.fee_vault.as_deref(), treasury_key)?;
 
     // ---------------------------------------------------------------
-    // Calculate distribution: 90% to winner, 10% treasury fee
-    // Uses u128 intermediate to avoid overflow on large wagers.
+    // Reentrancy guard. The runtime already rejects program -> token
+    // program -> program CPI chains; as a second line of defence the
+    // in-progress flag is written to account data before any CPI, so a
+    // re-entered payout would fail `begin_settling`.
     // ---------------------------------------------------------------
-    let total_pot = wager_lamports
-        .checked_mul(2)
-        .ok_or(EscrowError::InsufficientFunds)?;
-    let fee = (total_pot as u128)
-        .checked_mul(FEE_BPS as u128)
-        .ok_or(EscrowError::InsufficientFunds)?
-        .checked_div(10_000)
-        .ok_or(EscrowError::InsufficientFunds)? as u64;
-    let payout = total_pot
-        .checked_sub(fee)
-        .ok_or(EscrowError::InsufficientFunds)?;
+    accounts.escrow.begin_settling()?;
+    accounts.escrow.exit(&crate::ID)?;
+
+    profile!("pay_winner", "validated");
 
     // PDA signer seeds for CPI
     let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
 
+    let rent_to = if rent_to_winner {
+        accounts.winner.to_account_info()
+    } else {
+        accounts.rent_payer.to_account_info()
+    };
+
+    // Payout left in the escrow for `claim_winnings`, if any
+    let mut pending_payout = 0;
+
     // ---------------------------------------------------------------
     // Transfer funds
     // ---------------------------------------------------------------
     if is_native {
         // Native SOL: direct lamport manipulation (PDA owns the lamports).
-        // Remaining rent-exempt lamports are reclaimed by `close = authority`.
-        let escrow_info = ctx.accounts.escrow.to_account_info();
-        let winner_info = ctx.accounts.winner.to_account_info();
-        let treasury_info = ctx.accounts.treasury.to_account_info();
+        // Remaining rent-exempt lamports go to the rent payer when the escrow closes.
+        let escrow_info = accounts.escrow.to_account_info();
+        let recipient_info = match accounts.payout_recipient.as_ref() {
+            Some(recipient) => recipient.to_account_info(),
+            None => accounts.winner.to_account_info(),
+        };
+        let treasury_info = match accounts.fee_vault.as_ref() {
+            Some(vault) => vault.to_account_info(),
+            None => accounts.treasury.to_account_info(),
+        };
 
         require!(
             escrow_info.lamports() >= total_pot,
@@ -118,30 +306,44 @@ pub fn handler(ctx: Context<Settle>, winner: Pubkey) -> Result<()> {
         );
 
         **escrow_info.try_borrow_mut_lamports()? -= payout;
-        **winner_info.try_borrow_mut_lamports()? += payout;
+        **recipient_info.try_borrow_mut_lamports()? += payout;
+
+        **escrow_info.try_borrow_mut_lamports()? -= treasury_fee;
+        **treasury_info.try_borrow_mut_lamports()? += treasury_fee;
 
-        **escrow_info.try_borrow_mut_lamports()? -= fee;
-        **treasury_info.try_borrow_mut_lamports()? += fee;
+        if referral > 0 {
+            let referrer = accounts
+                .referrer
+                .as_ref()
+                .ok_or(EscrowError::InvalidReferrer)?;
+            require!(referrer.key() == referrer_key, EscrowError::InvalidReferrer);
+
+            **escrow_info.try_borrow_mut_lamports()? -= referral;
+            **referrer.try_borrow_mut_lamports()? += referral;
+        }
+
+        // Invariant: after the pot leaves, the escrow is either closed
+        // below or still rent-exempt. Never leave a rent-collectible
+        // account behind.
+        if retain_record {
+            let rent_floor = Rent::get()?.minimum_balance(escrow_info.data_len());
+            require!(
+                escrow_info.lamports() >= rent_floor,
+                EscrowError::BelowRentExempt
+            );
+        }
     } else {
         // SPL token: CPI transfer with PDA as signer
-        let escrow_ta = ctx
-            .accounts
+        let escrow_ta = accounts
             .escrow_token_account
             .as_ref()
             .ok_or(EscrowError::MissingSplAccount)?;
-        let winner_ta = ctx
-            .accounts
-            .winner_token_account
-            .as_ref()
-            .ok_or(EscrowError::MissingSplAccount)?;
-        let treasury_ta = ctx
-            .accounts
-            .treasury_token_account
+        let token_prog = accounts
+            .token_program
             .as_ref()
             .ok_or(EscrowError::MissingSplAccount)?;
-        let token_prog = ctx
-            .accounts
-            .token_program
+        let mint = accounts
+            .mint
             .as_ref()
             .ok_or(EscrowError::MissingSplAccount)?;
 
@@ -149,42 +351,260 @@ pub fn handler(ctx: Context<Settle>, winner: Pubkey) -> Result<()> {
             escrow_ta.amount >= total_pot,
             EscrowError::InsufficientFunds
         );
+        require_undelegated(escrow_ta)?;
+        require!(!escrow_ta.is_frozen(), EscrowError::TokenAccountFrozen);
 
-        // Payout to winner
-        token::transfer(
-            CpiContext::new_with_signer(
-                token_prog.to_account_info(),
-                SplTransfer {
-                    from: escrow_ta.to_account_info(),
-                    to: winner_ta.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            payout,
-        )?;
+        // WSOL escrows settled without a winner token account unwrap the
+        // payout: fees are paid in WSOL first, then the escrow's WSOL
+        // account is closed to the winner, releasing the remaining payout
+        // (and that account's rent) as native lamports. The account must
+        // hold exactly the pot so nothing else rides along.
+        let unwrap_payout =
+            token_mint == native_mint::ID && accounts.winner_token_account.is_none();
+        if recipient != winner {
+            // A redirected payout is paid out now, never held for
+            // `claim_winnings`, and must land with the chosen recipient
+            match accounts.winner_token_account.as_ref() {
+                Some(winner_ta) => {
+                    require!(winner_ta.owner == recipient, EscrowError::NotAuthorized);
+                    require!(winner_ta.mint == token_mint, EscrowError::WrongMint);
+                    require!(!winner_ta.is_frozen(), EscrowError::TokenAccountFrozen);
+                }
+                None => require!(unwrap_payout, EscrowError::MissingSplAccount),
+            }
+        } else if let Some(winner_ta) = accounts.winner_token_account.as_ref() {
+            // Otherwise the payout must land with the winner themselves
+            require!(winner_ta.owner == winner, EscrowError::WrongTokenOwner);
+        }
+        if unwrap_payout {
+            require!(
+                escrow_ta.amount == total_pot,
+                EscrowError::InsufficientFunds
+            );
+        } else if let Some(winner_ta) = accounts
+            .winner_token_account
+            .as_ref()
+            .filter(|winner_ta| !winner_ta.is_frozen())
+        {
+            // Payout to winner
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: winner_ta.to_account_info(),
+                        authority: accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                payout,
+                mint.decimals,
+            )?;
+        } else {
+            // The winner has no token account yet, or theirs is frozen.
+            // Rather than fail the whole settlement, collect the fee now and
+            // hold the payout until the winner calls `claim_winnings` with
+            // a usable one.
+            pending_payout = payout;
+        }
+
+        // Fee to treasury. Skipped for zero-rake escrows: a zero-amount
+        // CPI wastes compute and some token programs reject it.
+        if treasury_fee > 0 {
+            let treasury_ta = accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            // The fee must land with the treasury wallet checked above,
+            // or with its vault
+            require!(treasury_ta.owner == fee_to, EscrowError::NotAuthorized);
+            require!(treasury_ta.mint == token_mint, EscrowError::WrongMint);
+            require!(!treasury_ta.is_frozen(), EscrowError::TokenAccountFrozen);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: treasury_ta.to_account_info(),
+                        authority: accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                treasury_fee,
+                mint.decimals,
+            )?;
+        }
+
+        // Referrer's cut of the fee
+        if referral > 0 {
+            let referrer_ta = accounts
+                .referrer_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                referrer_ta.owner == referrer_key,
+                EscrowError::InvalidReferrer
+            );
+            require!(referrer_ta.mint == token_mint, EscrowError::WrongMint);
+            require!(!referrer_ta.is_frozen(), EscrowError::TokenAccountFrozen);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: referrer_ta.to_account_info(),
+                        authority: accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                referral,
+                mint.decimals,
+            )?;
+        }
+
+        // Deflationary share of the fee, destroyed rather than paid out
+        if burn > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    Burn {
+                        mint: mint.to_account_info(),
+                        from: escrow_ta.to_account_info(),
+                        authority: accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                burn,
+            )?;
+        }
 
-        // Fee to treasury
-        token::transfer(
-            CpiContext::new_with_signer(
+        if unwrap_payout {
+            token_interface::close_account(CpiContext::new_with_signer(
                 token_prog.to_account_info(),
-                SplTransfer {
-                    from: escrow_ta.to_account_info(),
-                    to: treasury_ta.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
+                CloseAccount {
+                    account: escrow_ta.to_account_info(),
+                    destination: match accounts.payout_recipient.as_ref() {
+                        Some(recipient) => recipient.to_account_info(),
+                        None => accounts.winner.to_account_info(),
+                    },
+                    authority: accounts.escrow.to_account_info(),
                 },
                 signer_seeds,
-            ),
-            fee,
-        )?;
+            ))?;
+        } else if pending_payout == 0 {
+            let escrow_ta = accounts
+                .escrow_token_account
+                .as_mut()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            close_escrow_ata_if_empty(
+                escrow_ta,
+                token_prog,
+                accounts.escrow.to_account_info(),
+                rent_to.clone(),
+                signer_seeds,
+            )?;
+        }
     }
 
+    profile!("pay_winner", "transferred");
+
     // ---------------------------------------------------------------
-    // Mark settled (mutable borrow after all CPI).
-    // The `close = authority` constraint reclaims rent after handler.
+    // Record the outcome (mutable borrow after all CPI), then close
+    // unless the escrow opted to stay readable until `sweep_closed`.
     // ---------------------------------------------------------------
-    let escrow = &mut ctx.accounts.escrow;
+    let escrow = &mut accounts.escrow;
     escrow.settled = true;
+    escrow.transition(EscrowStatus::Settled)?;
+    escrow.settling = false;
+    escrow.settled_at = Clock::get()?.unix_timestamp;
+    escrow.winner = winner;
+    escrow.pending_payout = pending_payout;
+    log_result(
+        accounts.result_log.as_deref_mut(),
+        treasury_key,
+        lobby_id_hash,
+        winner,
+        payout,
+    )?;
+    emit_accrual(
+        accounts.fee_vault.as_deref(),
+        lobby_id_hash,
+        token_mint,
+        treasury_fee,
+    );
+    index_remove(
+        [
+            accounts.host_index.as_deref_mut(),
+            accounts.opponent_index.as_deref_mut(),
+        ],
+        [escrow.host, escrow.opponent],
+        lobby_id_hash,
+    )?;
+    // A pending claim keeps the escrow open; `claim_winnings` closes it
+    let closed = !escrow.retain_record && pending_payout == 0;
+    if closed {
+        escrow.close(rent_to)?;
+    }
 
-    Ok(())
+    if pending_payout > 0 {
+        emit!(EscrowSettlePending {
+            lobby_id_hash,
+            winner,
+            payout,
+            fee,
+        });
+    }
+
+    if referral > 0 {
+        emit!(ReferralPaid {
+            lobby_id_hash,
+            referrer: referrer_key,
+            amount: referral,
+        });
+    }
+    if burn > 0 {
+        emit!(FeeBurned {
+            lobby_id_hash,
+            amount: burn,
+        });
+    }
+
+    if closed {
+        emit!(EscrowClosed {
+            lobby_id_hash,
+            winner,
+        });
+    }
+
+    Ok((payout, fee))
+}
+
+/// Closes the escrow's token account to `destination` once a payout has
+/// emptied it, reclaiming its rent. An account still holding tokens (e.g.
+/// sent to it directly) stays open for `withdraw_stuck_funds`.
+pub(crate) fn close_escrow_ata_if_empty<'info>(
+    escrow_ta: &mut InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    escrow: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    escrow_ta.reload()?;
+    if escrow_ta.amount > 0 {
+        return Ok(());
+    }
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow_ta.to_account_info(),
+            destination,
+            authority: escrow,
+        },
+        signer_seeds,
+    ))
 }
@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION};
+use crate::error::EscrowError;
+use crate::events::EscrowAgreedSettled;
+use crate::state::{EscrowStatus, MatchEscrow, ProgramConfig};
+
+/// Trustless negotiated settlement: both players sign off on an uneven
+/// split (e.g. 70/30 after a concession) and no authority is involved.
+/// `host_bps + opponent_bps` must equal `10_000 - fee_bps`; the treasury
+/// takes the rest, including any rounding remainder. Closes the escrow to
+/// its rent payer unless `retain_record`.
+#[derive(Accounts)]
+pub struct SettleAgreed<'info> {
+    #[account(mut)]
+    pub host: Signer<'info>,
+
+    #[account(mut)]
+    pub opponent: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = host @ EscrowError::NotAuthorized,
+        has_one = opponent @ EscrowError::NotAuthorized,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the escrow's fee destination in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Host's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Opponent's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account (only needed for SPL settlements with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL settlements.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL settlements.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Program config; only needed when the escrow uses the global treasury.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SettleAgreed>, host_bps: u16, opponent_bps: u16) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    let both_funded = ctx.accounts.escrow.both_funded();
+    let disputed = ctx.accounts.escrow.disputed;
+    let settled = ctx.accounts.escrow.settled;
+    let settling = ctx.accounts.escrow.settling;
+    let version = ctx.accounts.escrow.version;
+    let total_pot = ctx.accounts.escrow.total_pot()?;
+    let fee_bps = ctx.accounts.escrow.fee_bps;
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let treasury_key = if ctx.accounts.escrow.use_global_treasury {
+        ctx
+            .accounts
+            .config
+            .as_ref()
+            .ok_or(EscrowError::MissingConfig)?
+            .treasury
+    } else {
+        ctx.accounts.escrow.treasury
+    };
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(
+        host_bps as u32 + opponent_bps as u32 == 10_000 - fee_bps as u32,
+        EscrowError::InvalidSplit
+    );
+    require!(both_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(!settling, EscrowError::SettlementInProgress);
+    require!(!disputed, EscrowError::Disputed);
+    require!(version == ESCROW_VERSION, EscrowError::UnsupportedVersion);
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+
+    // ---------------------------------------------------------------
+    // Calculate distribution: each player's agreed share of the pot,
+    // rounded down; the treasury keeps the remainder.
+    // ---------------------------------------------------------------
+    let share = |bps: u16| -> Result<u64> {
+        u64::try_from((total_pot as u128) * (bps as u128) / 10_000)
+            .map_err(|_| error!(EscrowError::InsufficientFunds))
+    };
+    let host_share = share(host_bps)?;
+    let opponent_share = share(opponent_bps)?;
+    let fee = total_pot
+        .checked_sub(host_share)
+        .and_then(|v| v.checked_sub(opponent_share))
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Transfer funds
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports go to the rent payer when the escrow closes.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let host_info = ctx.accounts.host.to_account_info();
+        let opponent_info = ctx.accounts.opponent.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= host_share;
+        **host_info.try_borrow_mut_lamports()? += host_share;
+
+        **escrow_info.try_borrow_mut_lamports()? -= opponent_share;
+        **opponent_info.try_borrow_mut_lamports()? += opponent_share;
+
+        **escrow_info.try_borrow_mut_lamports()? -= fee;
+        **treasury_info.try_borrow_mut_lamports()? += fee;
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let host_ta = ctx
+            .accounts
+            .host_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let opponent_ta = ctx
+            .accounts
+            .opponent_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+        require!(host_ta.owner == host, EscrowError::WrongTokenOwner);
+        require!(opponent_ta.owner == opponent, EscrowError::WrongTokenOwner);
+
+        let mut transfers = vec![
+            (host_ta.to_account_info(), host_share),
+            (opponent_ta.to_account_info(), opponent_share),
+        ];
+        // Skipped for zero-rake escrows, as in `settle`
+        if fee > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                treasury_ta.owner == treasury_key,
+                EscrowError::NotAuthorized
+            );
+            require!(treasury_ta.mint == token_mint, EscrowError::WrongMint);
+            transfers.push((treasury_ta.to_account_info(), fee));
+        }
+
+        for (to, amount) in transfers {
+            // A 100/0 split leaves one side nothing to receive
+            if amount == 0 {
+                continue;
+            }
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to,
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Record the outcome (mutable borrow after all CPI), then close
+    // unless the escrow opted to stay readable until `sweep_closed`.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Settled)?;
+    escrow.settled_at = Clock::get()?.unix_timestamp;
+    // A split has no single winner, as with `settle_draw`
+    escrow.winner = Pubkey::default();
+    if !escrow.retain_record {
+        escrow.close(ctx.accounts.rent_payer.to_account_info())?;
+    }
+
+    emit!(EscrowAgreedSettled {
+        lobby_id_hash,
+        host_share,
+        opponent_share,
+        fee,
+    });
+
+    Ok(())
+}
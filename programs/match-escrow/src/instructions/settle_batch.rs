@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use crate::constants::{CONFIG_SEED, ESCROW_VERSION};
+use crate::error::EscrowError;
+use crate::events::EscrowSettled;
+use crate::state::{EscrowStatus, MatchEscrow, ProgramConfig};
+
+/// Settle-authority-only settlement of many native SOL escrows in one transaction,
+/// for tournament organizers.
+///
+/// Remaining accounts come in `(escrow, winner, rent_payer)` triples, all
+/// writable. Every escrow gets the same checks as `settle` and must pay its
+/// fee to the `treasury` passed here. The batch stops at the first invalid
+/// entry; its index is logged before the error is returned.
+///
+/// A transaction can lock at most 64 accounts, which caps a batch at about
+/// 20 entries with address lookup tables. Without them the 1232-byte
+/// packet limit caps it at roughly 10.
+#[derive(Accounts)]
+pub struct SettleBatch<'info> {
+    pub settle_authority: Signer<'info>,
+
+    /// CHECK: Validated against each escrow's fee destination in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Program config; only needed when an escrow uses the global treasury.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>) -> Result<()> {
+    let entries = ctx.remaining_accounts.chunks_exact(3);
+    require!(
+        !ctx.remaining_accounts.is_empty() && entries.remainder().is_empty(),
+        EscrowError::InvalidBatch
+    );
+
+    for (index, entry) in entries.enumerate() {
+        settle_entry(&ctx, &entry[0], &entry[1], &entry[2]).inspect_err(|_| {
+            msg!("settle_batch: entry {} rejected", index);
+        })?;
+        profile!("settle_batch", "entry settled");
+    }
+
+    Ok(())
+}
+
+fn settle_entry<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, SettleBatch<'info>>,
+    escrow_info: &'info AccountInfo<'info>,
+    winner_info: &'info AccountInfo<'info>,
+    rent_payer_info: &'info AccountInfo<'info>,
+) -> Result<()> {
+    let mut escrow = Account::<MatchEscrow>::try_from(escrow_info)?;
+
+    // Remaining accounts skip Anchor's constraints, so re-derive the PDA
+    let (expected, bump) = MatchEscrow::pda(&escrow.lobby_id_hash, ctx.program_id);
+    require!(
+        escrow_info.key() == expected && escrow.bump == bump,
+        EscrowError::NotAuthorized
+    );
+
+    let winner = winner_info.key();
+    let treasury_key = if escrow.use_global_treasury {
+        ctx
+            .accounts
+            .config
+            .as_ref()
+            .ok_or(EscrowError::MissingConfig)?
+            .treasury
+    } else {
+        escrow.treasury
+    };
+
+    // ---------------------------------------------------------------
+    // Validation (same rules as `settle`)
+    // ---------------------------------------------------------------
+    require!(
+        escrow.settle_authority == ctx.accounts.settle_authority.key(),
+        EscrowError::NotAuthorized
+    );
+    require!(
+        winner == escrow.host || winner == escrow.opponent,
+        EscrowError::InvalidWinner
+    );
+    require!(escrow.is_native_sol(), EscrowError::NativeOnly);
+    // Batch entries carry no referrer account to pay
+    require!(
+        escrow.referrer == Pubkey::default(),
+        EscrowError::InvalidReferrer
+    );
+    require!(
+        escrow.host_deposited && escrow.opponent_deposited,
+        EscrowError::EscrowNotFunded
+    );
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(!escrow.disputed, EscrowError::Disputed);
+    require!(
+        escrow.version == ESCROW_VERSION,
+        EscrowError::UnsupportedVersion
+    );
+    let clock = Clock::get()?;
+    escrow.check_challenge(&winner, &clock)?;
+    escrow.check_min_duration(&clock)?;
+    if escrow.rounds_to_win > 1 {
+        let wins = if winner == escrow.host {
+            escrow.host_wins
+        } else {
+            escrow.opponent_wins
+        };
+        require!(
+            wins >= escrow.rounds_to_win,
+            EscrowError::SeriesNotDecided
+        );
+    }
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+    require!(
+        rent_payer_info.key() == escrow.rent_payer,
+        EscrowError::NotAuthorized
+    );
+
+    let (total_pot, fee, payout) = escrow.compute_distribution()?;
+
+    // Reentrancy guard, as in `pay_winner`. No CPI follows, so the flag
+    // only needs to be set in memory and is cleared below.
+    escrow.begin_settling()?;
+
+    // ---------------------------------------------------------------
+    // Transfer funds (native SOL: direct lamport manipulation)
+    // ---------------------------------------------------------------
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+
+    require!(
+        escrow_info.lamports() >= total_pot,
+        EscrowError::InsufficientFunds
+    );
+
+    **escrow_info.try_borrow_mut_lamports()? -= payout;
+    **winner_info.try_borrow_mut_lamports()? += payout;
+
+    **escrow_info.try_borrow_mut_lamports()? -= fee;
+    **treasury_info.try_borrow_mut_lamports()? += fee;
+
+    // ---------------------------------------------------------------
+    // Record the outcome, then close or persist the account
+    // ---------------------------------------------------------------
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Settled)?;
+    escrow.settling = false;
+    escrow.settled_at = Clock::get()?.unix_timestamp;
+    escrow.winner = winner;
+
+    emit!(EscrowSettled {
+        lobby_id_hash: escrow.lobby_id_hash,
+        winner,
+        payout,
+        fee,
+        recipient: winner,
+    });
+
+    if escrow.retain_record {
+        escrow.exit(ctx.program_id)
+    } else {
+        escrow.close(rent_payer_info.clone())
+    }
+}
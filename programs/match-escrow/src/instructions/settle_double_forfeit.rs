@@ -0,0 +1,296 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION, MAX_FEE_BPS};
+use crate::error::EscrowError;
+use crate::events::DoubleForfeitSettled;
+use crate::instructions::deposit::require_undelegated;
+use crate::instructions::settle::close_escrow_ata_if_empty;
+use crate::state::{EscrowStatus, MatchEscrow, ProgramConfig};
+
+/// Authority-only resolution when both players abandon a funded match.
+/// Unlike a draw, nothing is pooled: each player gets their own deposit
+/// back minus an abandonment fee to the treasury. Closes the escrow to its
+/// rent payer unless `retain_record`.
+#[derive(Accounts)]
+pub struct SettleDoubleForfeit<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        has_one = host @ EscrowError::NotAuthorized,
+        has_one = opponent @ EscrowError::NotAuthorized,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; receives the host's refund.
+    #[account(mut)]
+    pub host: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by has_one; receives the opponent's refund.
+    #[account(mut)]
+    pub opponent: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the escrow's fee destination in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Host's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Opponent's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account (only needed for SPL refunds with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL refunds).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL refunds.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL refunds.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Program config; only needed when the escrow uses the global treasury.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SettleDoubleForfeit>, abandonment_fee_bps: u16) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    let host_deposited = ctx.accounts.escrow.host_deposited;
+    let opponent_deposited = ctx.accounts.escrow.opponent_deposited;
+    let disputed = ctx.accounts.escrow.disputed;
+    let settled = ctx.accounts.escrow.settled;
+    // Each side's stake includes its share of any sponsor bonus
+    let (host_stake, opponent_stake) = ctx.accounts.escrow.refund_amounts()?;
+    let total_pot = ctx.accounts.escrow.total_pot()?;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let retain_record = ctx.accounts.escrow.retain_record;
+    let treasury_key = if ctx.accounts.escrow.use_global_treasury {
+        ctx.accounts
+            .config
+            .as_ref()
+            .ok_or(EscrowError::MissingConfig)?
+            .treasury
+    } else {
+        ctx.accounts.escrow.treasury
+    };
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(
+        host_deposited && opponent_deposited,
+        EscrowError::EscrowNotFunded
+    );
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(!disputed, EscrowError::Disputed);
+    require!(
+        ctx.accounts.escrow.version == ESCROW_VERSION,
+        EscrowError::UnsupportedVersion
+    );
+    require!(abandonment_fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+
+    // ---------------------------------------------------------------
+    // Calculate refunds: each stake minus the abandonment fee.
+    // Uses u128 intermediate to avoid overflow on large stakes.
+    // ---------------------------------------------------------------
+    let fee_on = |stake: u64| -> Result<u64> {
+        Ok((stake as u128)
+            .checked_mul(abandonment_fee_bps as u128)
+            .ok_or(EscrowError::InsufficientFunds)?
+            .checked_div(10_000)
+            .ok_or(EscrowError::InsufficientFunds)? as u64)
+    };
+    let host_refund = host_stake
+        .checked_sub(fee_on(host_stake)?)
+        .ok_or(EscrowError::InsufficientFunds)?;
+    let opponent_refund = opponent_stake
+        .checked_sub(fee_on(opponent_stake)?)
+        .ok_or(EscrowError::InsufficientFunds)?;
+    let fee = total_pot
+        .checked_sub(host_refund)
+        .and_then(|v| v.checked_sub(opponent_refund))
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    // Reentrancy guard, as in `pay_winner`
+    ctx.accounts.escrow.begin_settling()?;
+    ctx.accounts.escrow.exit(&crate::ID)?;
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Return each stake, less the fee
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports go to the rent payer when the escrow closes.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let host_info = ctx.accounts.host.to_account_info();
+        let opponent_info = ctx.accounts.opponent.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= host_refund;
+        **host_info.try_borrow_mut_lamports()? += host_refund;
+
+        **escrow_info.try_borrow_mut_lamports()? -= opponent_refund;
+        **opponent_info.try_borrow_mut_lamports()? += opponent_refund;
+
+        **escrow_info.try_borrow_mut_lamports()? -= fee;
+        **treasury_info.try_borrow_mut_lamports()? += fee;
+
+        if retain_record {
+            let rent_floor = Rent::get()?.minimum_balance(escrow_info.data_len());
+            require!(
+                escrow_info.lamports() >= rent_floor,
+                EscrowError::BelowRentExempt
+            );
+        }
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let host_ta = ctx
+            .accounts
+            .host_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let opponent_ta = ctx
+            .accounts
+            .opponent_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        // Refunds must land with the players themselves
+        require!(host_ta.owner == host, EscrowError::NotAuthorized);
+        require!(opponent_ta.owner == opponent, EscrowError::NotAuthorized);
+        require!(host_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(opponent_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+        require_undelegated(escrow_ta)?;
+
+        let mut transfers = vec![
+            (host_ta.to_account_info(), host_refund),
+            (opponent_ta.to_account_info(), opponent_refund),
+        ];
+        // Zero-fee resolutions skip the treasury CPI entirely
+        if fee > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                treasury_ta.owner == treasury_key,
+                EscrowError::NotAuthorized
+            );
+            require!(treasury_ta.mint == token_mint, EscrowError::WrongMint);
+            transfers.push((treasury_ta.to_account_info(), fee));
+        }
+
+        for (to, amount) in transfers {
+            // Free-entry seats may have staked nothing
+            if amount == 0 {
+                continue;
+            }
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to,
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        close_escrow_ata_if_empty(
+            escrow_ta,
+            token_prog,
+            ctx.accounts.escrow.to_account_info(),
+            ctx.accounts.rent_payer.to_account_info(),
+            signer_seeds,
+        )?;
+    }
+
+    // ---------------------------------------------------------------
+    // Mark settled (mutable borrow after all CPI), then close unless the
+    // escrow opted to stay readable until `sweep_closed`.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Refunded)?;
+    escrow.settling = false;
+    escrow.settled_at = Clock::get()?.unix_timestamp;
+    if !retain_record {
+        escrow.close(ctx.accounts.rent_payer.to_account_info())?;
+    }
+
+    emit!(DoubleForfeitSettled {
+        lobby_id_hash,
+        host_refund,
+        opponent_refund,
+        fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,385 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED, ESCROW_VERSION, FEE_VAULT_SEED};
+use crate::error::EscrowError;
+use crate::events::{EscrowDrawn, FeeBurned, ReferralPaid};
+use crate::instructions::deposit::require_undelegated;
+use crate::instructions::fee_vault::{emit_accrual, fee_recipient};
+use crate::instructions::settle::close_escrow_ata_if_empty;
+use crate::state::{EscrowStatus, FeeVault, MatchEscrow, ProgramConfig};
+
+/// Splits the pot between both players after a drawn match. The fee is
+/// taken exactly as in `settle` (rounding, `min_fee`/`max_fee`, referral
+/// and burn shares); the rest is shared in proportion to each stake.
+/// Like `settle`, waits out `min_match_duration` and is blocked by a
+/// pending `settle_with_challenge` proposal. Closes the escrow to its rent
+/// payer unless `retain_record`.
+#[derive(Accounts)]
+pub struct SettleDraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        has_one = rent_payer @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// CHECK: Validated by has_one; only receives the reclaimed rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against escrow.host in handler.
+    #[account(mut)]
+    pub host: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against escrow.opponent in handler.
+    #[account(mut)]
+    pub opponent: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the escrow's fee destination in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Host's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub host_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Opponent's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub opponent_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account (only needed for SPL settlements with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked` and writable for
+    /// fee burns. Only needed for SPL settlements.
+    #[account(mut, address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL settlements.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Program config; only needed when the escrow uses the global treasury.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
+    /// CHECK: Validated against escrow.referrer in handler. Only needed for
+    /// native settlements of referred escrows.
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// Referrer's token account (only needed for SPL settlements of referred escrows).
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's fee vault; when passed, the treasury fee accrues there for
+    /// `sweep_fees`, as in `settle`.
+    #[account(
+        mut,
+        seeds = [FEE_VAULT_SEED, fee_vault.treasury.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Option<Box<Account<'info, FeeVault>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SettleDraw>) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // ---------------------------------------------------------------
+    let host = ctx.accounts.escrow.host;
+    let opponent = ctx.accounts.escrow.opponent;
+    let both_funded = ctx.accounts.escrow.both_funded();
+    let disputed = ctx.accounts.escrow.disputed;
+    let settled = ctx.accounts.escrow.settled;
+    let host_wager = ctx.accounts.escrow.host_deposited_amount;
+    let opponent_wager = ctx.accounts.escrow.opponent_deposited_amount;
+    let deposited = ctx.accounts.escrow.total_deposited()?;
+    let (total_pot, fee, distributable) = ctx.accounts.escrow.compute_distribution()?;
+    let (referral, burn, treasury_fee) = ctx.accounts.escrow.split_fee(total_pot, fee)?;
+    let referrer_key = ctx.accounts.escrow.referrer;
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let retain_record = ctx.accounts.escrow.retain_record;
+    let treasury_key = if ctx.accounts.escrow.use_global_treasury {
+        ctx
+            .accounts
+            .config
+            .as_ref()
+            .ok_or(EscrowError::MissingConfig)?
+            .treasury
+    } else {
+        ctx.accounts.escrow.treasury
+    };
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(both_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(!disputed, EscrowError::Disputed);
+    require!(
+        ctx.accounts.escrow.version == ESCROW_VERSION,
+        EscrowError::UnsupportedVersion
+    );
+    require!(
+        ctx.accounts.host.key() == host && ctx.accounts.opponent.key() == opponent,
+        EscrowError::NotAuthorized
+    );
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+    let fee_to = fee_recipient(ctx.accounts.fee_vault.as_deref(), treasury_key)?;
+    // A draw names no winner, so it can't land over a pending proposal
+    let clock = Clock::get()?;
+    ctx.accounts.escrow.check_challenge(&Pubkey::default(), &clock)?;
+    ctx.accounts.escrow.check_min_duration(&clock)?;
+
+    // ---------------------------------------------------------------
+    // Calculate distribution: the fee comes off the top as in `settle`,
+    // the remainder is split in proportion to each stake (50/50 for even
+    // wagers). Any rounding remainder goes to the treasury so the outcome
+    // never depends on account ordering.
+    // ---------------------------------------------------------------
+    let share = |stake: u64| -> u64 {
+        if deposited == 0 {
+            distributable / 2
+        } else {
+            ((distributable as u128) * (stake as u128) / (deposited as u128)) as u64
+        }
+    };
+    let host_share = share(host_wager);
+    let opponent_share = share(opponent_wager);
+    let treasury_amount = distributable
+        .checked_sub(host_share)
+        .and_then(|v| v.checked_sub(opponent_share))
+        .and_then(|dust| dust.checked_add(treasury_fee))
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    // Reentrancy guard, as in `pay_winner`
+    ctx.accounts.escrow.begin_settling()?;
+    ctx.accounts.escrow.exit(&crate::ID)?;
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Transfer funds
+    // ---------------------------------------------------------------
+    if is_native {
+        // Remaining rent-exempt lamports go to the rent payer when the escrow closes.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let host_info = ctx.accounts.host.to_account_info();
+        let opponent_info = ctx.accounts.opponent.to_account_info();
+        let treasury_info = match ctx.accounts.fee_vault.as_ref() {
+            Some(vault) => vault.to_account_info(),
+            None => ctx.accounts.treasury.to_account_info(),
+        };
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        **escrow_info.try_borrow_mut_lamports()? -= host_share;
+        **host_info.try_borrow_mut_lamports()? += host_share;
+
+        **escrow_info.try_borrow_mut_lamports()? -= opponent_share;
+        **opponent_info.try_borrow_mut_lamports()? += opponent_share;
+
+        **escrow_info.try_borrow_mut_lamports()? -= treasury_amount;
+        **treasury_info.try_borrow_mut_lamports()? += treasury_amount;
+
+        if referral > 0 {
+            let referrer = ctx
+                .accounts
+                .referrer
+                .as_ref()
+                .ok_or(EscrowError::InvalidReferrer)?;
+            require!(referrer.key() == referrer_key, EscrowError::InvalidReferrer);
+
+            **escrow_info.try_borrow_mut_lamports()? -= referral;
+            **referrer.try_borrow_mut_lamports()? += referral;
+        }
+
+        if retain_record {
+            let rent_floor = Rent::get()?.minimum_balance(escrow_info.data_len());
+            require!(
+                escrow_info.lamports() >= rent_floor,
+                EscrowError::BelowRentExempt
+            );
+        }
+    } else {
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let host_ta = ctx
+            .accounts
+            .host_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let opponent_ta = ctx
+            .accounts
+            .opponent_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+        require_undelegated(escrow_ta)?;
+        require!(host_ta.owner == host, EscrowError::WrongTokenOwner);
+        require!(opponent_ta.owner == opponent, EscrowError::WrongTokenOwner);
+
+        let mut transfers = vec![
+            (host_ta.to_account_info(), host_share),
+            (opponent_ta.to_account_info(), opponent_share),
+        ];
+        // Skipped for zero-rake escrows, as in `settle`
+        if treasury_amount > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(treasury_ta.owner == fee_to, EscrowError::NotAuthorized);
+            require!(treasury_ta.mint == token_mint, EscrowError::WrongMint);
+            transfers.push((treasury_ta.to_account_info(), treasury_amount));
+        }
+        if referral > 0 {
+            let referrer_ta = ctx
+                .accounts
+                .referrer_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+            require!(
+                referrer_ta.owner == referrer_key,
+                EscrowError::InvalidReferrer
+            );
+            require!(referrer_ta.mint == token_mint, EscrowError::WrongMint);
+            transfers.push((referrer_ta.to_account_info(), referral));
+        }
+
+        for (to, amount) in transfers {
+            // Free-entry seats may have no share to receive
+            if amount == 0 {
+                continue;
+            }
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to,
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+
+        // Deflationary share of the fee, destroyed rather than paid out
+        if burn > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    Burn {
+                        mint: mint.to_account_info(),
+                        from: escrow_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                burn,
+            )?;
+        }
+
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        close_escrow_ata_if_empty(
+            escrow_ta,
+            token_prog,
+            ctx.accounts.escrow.to_account_info(),
+            ctx.accounts.rent_payer.to_account_info(),
+            signer_seeds,
+        )?;
+    }
+
+    // ---------------------------------------------------------------
+    // Record the outcome (mutable borrow after all CPI), then close
+    // unless the escrow opted to stay readable until `sweep_closed`.
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.settled = true;
+    escrow.transition(EscrowStatus::Settled)?;
+    escrow.settling = false;
+    escrow.settled_at = Clock::get()?.unix_timestamp;
+    // A draw has no winner; the default key marks the split outcome.
+    escrow.winner = Pubkey::default();
+    emit_accrual(
+        ctx.accounts.fee_vault.as_deref(),
+        lobby_id_hash,
+        token_mint,
+        treasury_amount,
+    );
+    if !retain_record {
+        escrow.close(ctx.accounts.rent_payer.to_account_info())?;
+    }
+
+    emit!(EscrowDrawn {
+        lobby_id_hash,
+        host,
+        opponent,
+        host_share,
+        opponent_share,
+        fee: treasury_amount,
+    });
+
+    if referral > 0 {
+        emit!(ReferralPaid {
+            lobby_id_hash,
+            referrer: referrer_key,
+            amount: referral,
+        });
+    }
+    if burn > 0 {
+        emit!(FeeBurned {
+            lobby_id_hash,
+            amount: burn,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use crate::constants::ED25519_PROGRAM_ID;
+use crate::error::EscrowError;
+use crate::events::EscrowSettled;
+use crate::instructions::settle::*;
+
+/// Size of the ed25519 program's instruction header for a single signature:
+/// signature count, padding, then seven little-endian u16 offsets.
+const ED25519_HEADER_LEN: usize = 16;
+
+/// Settlement where an external result oracle, not the fee-collecting
+/// server, names the winner. The settle authority still submits the
+/// transaction, but it must be immediately preceded by an ed25519 program
+/// instruction in which the escrow's oracle signed `lobby_id_hash || winner`.
+#[derive(Accounts)]
+pub struct SettleWithOracleSig<'info> {
+    pub settle: Settle<'info>,
+
+    /// CHECK: Address-checked instructions sysvar, read for the ed25519 instruction.
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<SettleWithOracleSig>, winner: Pubkey) -> Result<()> {
+    let oracle = ctx.accounts.settle.payout.escrow.oracle;
+    let lobby_id_hash = ctx.accounts.settle.payout.escrow.lobby_id_hash;
+    require!(oracle != Pubkey::default(), EscrowError::InvalidOracleSig);
+
+    // The signature itself is checked by the ed25519 program, which fails
+    // the whole transaction on a bad signature. Here we only confirm it was
+    // the oracle signing this exact result.
+    let sig_ix = sysvar_instructions::get_instruction_relative(-1, &ctx.accounts.instructions)
+        .map_err(|_| error!(EscrowError::InvalidOracleSig))?;
+    let mut message = [0u8; 64];
+    message[..32].copy_from_slice(&lobby_id_hash);
+    message[32..].copy_from_slice(winner.as_ref());
+    verify_oracle_signature(&sig_ix, &oracle, &message)?;
+
+    let (payout, fee) = pay_winner(&mut ctx.accounts.settle.payout, winner, false)?;
+
+    emit!(EscrowSettled {
+        lobby_id_hash,
+        winner,
+        payout,
+        fee,
+        recipient: ctx.accounts.settle.payout.recipient(winner),
+    });
+
+    Ok(())
+}
+
+/// Checks that `ix` is a single-signature ed25519 verification of `message`
+/// by `oracle`, with every offset pointing into `ix` itself.
+fn verify_oracle_signature(ix: &Instruction, oracle: &Pubkey, message: &[u8]) -> Result<()> {
+    require!(
+        ix.program_id == ED25519_PROGRAM_ID && ix.accounts.is_empty(),
+        EscrowError::InvalidOracleSig
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN && data[0] == 1,
+        EscrowError::InvalidOracleSig
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_ix_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_ix_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix_index = read_u16(14);
+
+    // u16::MAX means "this instruction"; anything else could point the
+    // verifier at data the oracle never signed for us.
+    require!(
+        signature_ix_index == u16::MAX
+            && public_key_ix_index == u16::MAX
+            && message_ix_index == u16::MAX,
+        EscrowError::InvalidOracleSig
+    );
+
+    let signer = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(EscrowError::InvalidOracleSig)?;
+    let signed = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(EscrowError::InvalidOracleSig)?;
+    require!(
+        signer == oracle.as_ref() && signed == message,
+        EscrowError::InvalidOracleSig
+    );
+
+    Ok(())
+}
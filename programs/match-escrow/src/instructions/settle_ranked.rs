@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::MULTI_ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::MultiEscrowRanked;
+use crate::state::MultiMatchEscrow;
+
+/// Authority-only payout of a multiplayer pot to several finishers at once,
+/// e.g. 60/30/10 to 1st/2nd/3rd.
+///
+/// `finishers[i]` receives `payout_bps[i]` of the pot; the table must sum to
+/// `10_000 - fee_bps`. Recipient accounts are passed as remaining accounts
+/// in finisher order: the finisher wallets for native SOL, or their token
+/// accounts for SPL. Rounding dust goes to the treasury with the fee.
+#[derive(Accounts)]
+pub struct SettleRanked<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MULTI_ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, MultiMatchEscrow>,
+
+    /// CHECK: Validated against escrow.treasury in handler.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Treasury's token account (only needed for SPL settlements with a fee).
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account (only needed for SPL settlements).
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL settlements.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL settlements.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleRanked<'info>>,
+    finishers: Vec<Pubkey>,
+    payout_bps: Vec<u16>,
+) -> Result<()> {
+    // ---------------------------------------------------------------
+    // Extract all values from escrow before any transfers.
+    // Avoids E0502 when we need &mut ctx.accounts.escrow later.
+    // ---------------------------------------------------------------
+    let players = ctx.accounts.escrow.players.clone();
+    let fully_funded = ctx.accounts.escrow.fully_funded();
+    let settled = ctx.accounts.escrow.settled;
+    let total_pot = ctx.accounts.escrow.total_pot()?;
+    let fee_bps = ctx.accounts.escrow.fee_bps;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let treasury_key = ctx.accounts.escrow.treasury;
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+    let bump = ctx.accounts.escrow.bump;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(fully_funded, EscrowError::EscrowNotFunded);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        ctx.accounts.treasury.key() == treasury_key,
+        EscrowError::NotAuthorized
+    );
+    require!(
+        !finishers.is_empty()
+            && finishers.len() == payout_bps.len()
+            && finishers.len() <= players.len(),
+        EscrowError::InvalidPayoutTable
+    );
+    require!(
+        ctx.remaining_accounts.len() == finishers.len(),
+        EscrowError::InvalidPayoutTable
+    );
+    for (i, finisher) in finishers.iter().enumerate() {
+        require!(players.contains(finisher), EscrowError::InvalidWinner);
+        require!(
+            !finishers[..i].contains(finisher),
+            EscrowError::InvalidPayoutTable
+        );
+    }
+    let table_bps = payout_bps
+        .iter()
+        .try_fold(0u16, |sum, bps| sum.checked_add(*bps))
+        .ok_or(EscrowError::InvalidPayoutTable)?;
+    require!(
+        table_bps as u32 + fee_bps as u32 == 10_000,
+        EscrowError::InvalidPayoutTable
+    );
+
+    // ---------------------------------------------------------------
+    // Calculate distribution: each finisher's share of the pot, with the
+    // fee plus any rounding remainder to the treasury.
+    // Uses u128 intermediate to avoid overflow on large pots.
+    // ---------------------------------------------------------------
+    let mut payouts = Vec::with_capacity(finishers.len());
+    for bps in payout_bps.iter() {
+        let share = (total_pot as u128)
+            .checked_mul(*bps as u128)
+            .ok_or(EscrowError::InsufficientFunds)?
+            .checked_div(10_000)
+            .ok_or(EscrowError::InsufficientFunds)? as u64;
+        payouts.push(share);
+    }
+    let paid: u64 = payouts.iter().sum();
+    let fee = total_pot
+        .checked_sub(paid)
+        .ok_or(EscrowError::InsufficientFunds)?;
+
+    profile!("settle_ranked", "validated");
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[MULTI_ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    // ---------------------------------------------------------------
+    // Transfer funds
+    // ---------------------------------------------------------------
+    if is_native {
+        // Native SOL: direct lamport manipulation (PDA owns the lamports).
+        // Remaining rent-exempt lamports are reclaimed by `close = authority`.
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+
+        require!(
+            escrow_info.lamports() >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        for ((finisher, recipient), share) in finishers
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .zip(payouts.iter())
+        {
+            require!(recipient.key() == *finisher, EscrowError::InvalidWinner);
+            **escrow_info.try_borrow_mut_lamports()? -= *share;
+            **recipient.try_borrow_mut_lamports()? += *share;
+        }
+
+        **escrow_info.try_borrow_mut_lamports()? -= fee;
+        **treasury_info.try_borrow_mut_lamports()? += fee;
+    } else {
+        // SPL token: one CPI per finisher with PDA as signer
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(
+            escrow_ta.amount >= total_pot,
+            EscrowError::InsufficientFunds
+        );
+
+        for ((finisher, recipient), share) in finishers
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .zip(payouts.iter())
+        {
+            let recipient_ta = InterfaceAccount::<TokenAccount>::try_from(recipient)?;
+            require!(recipient_ta.owner == *finisher, EscrowError::InvalidWinner);
+            require!(recipient_ta.mint == token_mint, EscrowError::WrongMint);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: recipient.clone(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                *share,
+                mint.decimals,
+            )?;
+        }
+
+        // Fee to treasury. Skipped for zero-rake escrows: a zero-amount
+        // CPI wastes compute and some token programs reject it.
+        if fee > 0 {
+            let treasury_ta = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingSplAccount)?;
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: treasury_ta.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                fee,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    profile!("settle_ranked", "transferred");
+
+    // ---------------------------------------------------------------
+    // Mark settled (mutable borrow after all CPI).
+    // The `close = authority` constraint reclaims rent after handler.
+    // ---------------------------------------------------------------
+    ctx.accounts.escrow.settled = true;
+
+    emit!(MultiEscrowRanked {
+        lobby_id_hash,
+        finishers,
+        payouts,
+        fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::error::EscrowError;
+use crate::events::EscrowSettled;
+use crate::instructions::settle::*;
+
+/// Trustless settlement co-signed by both players. Requiring the host and
+/// opponent to sign the same transaction means neither the server authority
+/// nor a single player can name the winner alone. Otherwise identical to
+/// `settle`: same accounts after the two signers, same distribution, and
+/// rent returns to the rent payer.
+#[derive(Accounts)]
+pub struct SettleSigned<'info> {
+    #[account(address = payout.escrow.host @ EscrowError::NotAuthorized)]
+    pub host: Signer<'info>,
+
+    #[account(address = payout.escrow.opponent @ EscrowError::NotAuthorized)]
+    pub opponent: Signer<'info>,
+
+    pub payout: Payout<'info>,
+}
+
+pub fn handler(ctx: Context<SettleSigned>, winner: Pubkey) -> Result<()> {
+    let (payout, fee) = pay_winner(&mut ctx.accounts.payout, winner, false)?;
+
+    emit!(EscrowSettled {
+        lobby_id_hash: ctx.accounts.payout.escrow.lobby_id_hash,
+        winner,
+        payout,
+        fee,
+        recipient: ctx.accounts.payout.recipient(winner),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::error::EscrowError;
+use crate::events::TiebreakSettled;
+use crate::instructions::settle::{pay_winner, Settle};
+
+/// Settle-authority-only resolution of a tie in game modes where the
+/// player who deposited first takes the pot. Unlike `settle_draw`, which
+/// splits the pot, the whole pot less the fee goes to `first_depositor`,
+/// through the same accounts and distribution as `settle`.
+pub fn handler(ctx: Context<Settle>) -> Result<()> {
+    let winner = ctx.accounts.payout.escrow.first_depositor;
+    require!(winner != Pubkey::default(), EscrowError::NoDepositOrder);
+
+    let (payout, fee) = pay_winner(&mut ctx.accounts.payout, winner, false)?;
+
+    emit!(TiebreakSettled {
+        lobby_id_hash: ctx.accounts.payout.escrow.lobby_id_hash,
+        winner,
+        payout,
+        fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED};
+use crate::error::EscrowError;
+use crate::events::SponsorDeposited;
+use crate::instructions::deposit::{net_of_transfer_fee, require_undelegated};
+use crate::state::{DepositMethod, EscrowStatus, MatchEscrow, ProgramConfig};
+
+/// Funds both seats of a `sponsored` escrow from the authority's wallet,
+/// for promotional free rolls. The stakes are credited to the seats as if
+/// each player had deposited, so the usual settle paths apply and any
+/// refund goes to the players. Sponsored escrows take no fee.
+#[derive(Accounts)]
+pub struct SponsorDeposit<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Authority's token account (only needed for SPL deposits).
+    #[account(mut)]
+    pub authority_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token account, only needed for SPL deposits. Must be the
+    /// escrow PDA's associated token account.
+    #[account(mut)]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's token mint, required by `transfer_checked`. Only needed for SPL deposits.
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program (legacy SPL Token or Token-2022). Only needed for SPL deposits.
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SponsorDeposit>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, EscrowError::ProgramPaused);
+
+    // ---------------------------------------------------------------
+    // Extract all needed values BEFORE any CPI calls.
+    // ---------------------------------------------------------------
+    let sponsor = ctx.accounts.authority.key();
+    let host_wager = ctx.accounts.escrow.host_wager;
+    let opponent_wager = ctx.accounts.escrow.opponent_wager;
+    let settled = ctx.accounts.escrow.settled;
+    let is_native = ctx.accounts.escrow.is_native_sol();
+    let token_mint = ctx.accounts.escrow.token_mint;
+    let escrow_key = ctx.accounts.escrow.key();
+    let deposit_window_open = ctx.accounts.escrow.deposit_window_open(&Clock::get()?);
+    let lobby_id_hash = ctx.accounts.escrow.lobby_id_hash;
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(ctx.accounts.escrow.sponsored, EscrowError::NotSponsored);
+    require!(!settled, EscrowError::AlreadySettled);
+    require!(
+        ctx.accounts.escrow.total_deposited()? == 0,
+        EscrowError::AlreadyDeposited
+    );
+    require!(deposit_window_open, EscrowError::DepositWindowClosed);
+
+    // ---------------------------------------------------------------
+    // Transfer both stakes into the escrow, one transfer per seat so
+    // transfer-fee mints credit each seat the same as `deposit_both`
+    // ---------------------------------------------------------------
+    let (received, host_net, opponent_net) = if is_native {
+        let balance_before = ctx.accounts.escrow.to_account_info().lamports();
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                },
+            ),
+            host_wager
+                .checked_add(opponent_wager)
+                .ok_or(EscrowError::InsufficientFunds)?,
+        )?;
+
+        let received = ctx
+            .accounts
+            .escrow
+            .to_account_info()
+            .lamports()
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        (received, host_wager, opponent_wager)
+    } else {
+        let authority_ta = ctx
+            .accounts
+            .authority_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let token_prog = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(EscrowError::MissingSplAccount)?;
+
+        require!(authority_ta.owner == sponsor, EscrowError::WrongTokenOwner);
+        require!(authority_ta.mint == token_mint, EscrowError::WrongMint);
+        require!(
+            escrow_ta.key()
+                == get_associated_token_address_with_program_id(
+                    &escrow_key,
+                    &token_mint,
+                    &token_prog.key(),
+                ),
+            EscrowError::InvalidEscrowTokenAccount
+        );
+        require_undelegated(escrow_ta)?;
+
+        let host_net = net_of_transfer_fee(mint, host_wager)?;
+        let opponent_net = net_of_transfer_fee(mint, opponent_wager)?;
+        let balance_before = escrow_ta.amount;
+
+        for amount in [host_wager, opponent_wager] {
+            // Free-entry seats may stake nothing
+            if amount == 0 {
+                continue;
+            }
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    token_prog.to_account_info(),
+                    TransferChecked {
+                        from: authority_ta.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: escrow_ta.to_account_info(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                amount,
+                mint.decimals,
+            )?;
+        }
+
+        // Re-read the escrow balance to catch mints that skim on transfer.
+        let escrow_ta = ctx
+            .accounts
+            .escrow_token_account
+            .as_mut()
+            .ok_or(EscrowError::MissingSplAccount)?;
+        escrow_ta.reload()?;
+        let received = escrow_ta
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(EscrowError::DepositShortfall)?;
+        (received, host_net, opponent_net)
+    };
+    require!(
+        Some(received) == host_net.checked_add(opponent_net),
+        EscrowError::DepositShortfall
+    );
+
+    // ---------------------------------------------------------------
+    // Record both seats as funded (mutable borrow after all CPI)
+    // ---------------------------------------------------------------
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.host_deposited = true;
+    escrow.opponent_deposited = true;
+    escrow.host_deposited_amount = host_net;
+    escrow.opponent_deposited_amount = opponent_net;
+    escrow.set_deposit_method(true, DepositMethod::OnChain)?;
+    escrow.set_deposit_method(false, DepositMethod::OnChain)?;
+    // Both stakes land in one instruction; the host counts as first
+    escrow.first_depositor = escrow.host;
+    escrow.funded_at = Clock::get()?.unix_timestamp;
+    escrow.transition(EscrowStatus::Funded)?;
+
+    emit!(SponsorDeposited {
+        lobby_id_hash,
+        sponsor,
+        host_amount: host_net,
+        opponent_amount: opponent_net,
+    });
+
+    Ok(())
+}
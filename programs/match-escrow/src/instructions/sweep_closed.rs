@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::EscrowSwept;
+use crate::state::MatchEscrow;
+
+/// Authority-only cleanup of a settled escrow that was kept open with
+/// `retain_record`. Closes the account and returns its rent to the authority.
+#[derive(Accounts)]
+pub struct SweepClosed<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+        close = authority,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<SweepClosed>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+
+    require!(escrow.settled, EscrowError::NotSettled);
+    require!(escrow.pending_payout == 0, EscrowError::PayoutPending);
+
+    emit!(EscrowSwept {
+        lobby_id_hash: escrow.lobby_id_hash,
+        winner: escrow.winner,
+        settled_at: escrow.settled_at,
+    });
+
+    Ok(())
+}
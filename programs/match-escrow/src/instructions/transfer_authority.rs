@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::constants::ESCROW_SEED;
+use crate::error::EscrowError;
+use crate::events::AuthorityTransferred;
+use crate::state::MatchEscrow;
+
+/// Hands control of an in-flight escrow to a new server key, e.g. during
+/// key rotation. The old authority loses all settle/forfeit rights; a
+/// separately configured `settle_authority` keeps its own.
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+
+    let old_authority = escrow.authority;
+    escrow.authority = new_authority;
+    // A settle authority left at its default follows the rotation; a
+    // separately configured one is kept
+    if escrow.settle_authority == old_authority {
+        escrow.settle_authority = new_authority;
+    }
+
+    emit!(AuthorityTransferred {
+        lobby_id_hash: escrow.lobby_id_hash,
+        old_authority,
+        new_authority,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::constants::{ESCROW_SEED, MAX_WAGER_LAMPORTS, MIN_WAGER_LAMPORTS};
+use crate::error::EscrowError;
+use crate::events::WagerUpdated;
+use crate::state::MatchEscrow;
+
+/// Authority-only change of stakes while the lobby is still open, e.g. when
+/// the host bumps the buy-in. Locked as soon as either player deposits.
+#[derive(Accounts)]
+pub struct UpdateWager<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+        has_one = authority @ EscrowError::NotAuthorized,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+}
+
+pub fn handler(ctx: Context<UpdateWager>, host_wager: u64, opponent_wager: u64) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(!escrow.settled, EscrowError::AlreadySettled);
+    require!(
+        !escrow.host_deposited && !escrow.opponent_deposited && escrow.total_deposited()? == 0,
+        EscrowError::CannotModifyFunded
+    );
+    require!(
+        escrow.free_entry || (host_wager > 0 && opponent_wager > 0),
+        EscrowError::ZeroWager
+    );
+
+    // Per-escrow bounds are not stored, so native escrows fall back to the
+    // program-wide limits
+    if escrow.is_native_sol() {
+        for wager in [host_wager, opponent_wager] {
+            if !(escrow.free_entry && wager == 0) {
+                require!(wager >= MIN_WAGER_LAMPORTS, EscrowError::WagerTooSmall);
+            }
+            require!(wager <= MAX_WAGER_LAMPORTS, EscrowError::WagerTooLarge);
+        }
+    }
+
+    let old_host_wager = escrow.host_wager;
+    let old_opponent_wager = escrow.opponent_wager;
+    escrow.host_wager = host_wager;
+    escrow.opponent_wager = opponent_wager;
+
+    emit!(WagerUpdated {
+        lobby_id_hash: escrow.lobby_id_hash,
+        old_host_wager,
+        old_opponent_wager,
+        host_wager,
+        opponent_wager,
+    });
+
+    Ok(())
+}
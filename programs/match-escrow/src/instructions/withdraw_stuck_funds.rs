@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{CONFIG_SEED, ESCROW_SEED};
+use crate::error::EscrowError;
+use crate::events::StuckFundsWithdrawn;
+use crate::state::{MatchEscrow, ProgramConfig};
+
+/// Config-admin recovery for tokens left behind in a settled escrow's token
+/// account (e.g. a fee-on-transfer remainder). Sweeps the whole balance to
+/// the escrow's fee destination. Never runs on an unsettled escrow, so it
+/// can't touch live stakes; the escrow must have been kept with
+/// `retain_record` and not yet swept.
+#[derive(Accounts)]
+pub struct WithdrawStuckFunds<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ EscrowError::NotAuthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [ESCROW_SEED, escrow.lobby_id_hash.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MatchEscrow>,
+
+    /// Escrow's token account holding the leftover balance.
+    #[account(mut)]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury's token account receiving the sweep.
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow.token_mint @ EscrowError::WrongMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token program (legacy SPL Token or Token-2022).
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<WithdrawStuckFunds>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    let escrow_ta = &ctx.accounts.escrow_token_account;
+    let treasury_ta = &ctx.accounts.treasury_token_account;
+
+    let treasury = if escrow.use_global_treasury {
+        ctx.accounts.config.treasury
+    } else {
+        escrow.treasury
+    };
+
+    // ---------------------------------------------------------------
+    // Validation
+    // ---------------------------------------------------------------
+    require!(escrow.settled, EscrowError::NotSettled);
+    // An unclaimed payout is the winner's, not leftover dust
+    require!(escrow.pending_payout == 0, EscrowError::PayoutPending);
+    require!(
+        escrow_ta.owner == escrow.key(),
+        EscrowError::InvalidEscrowTokenAccount
+    );
+    require!(escrow_ta.mint == escrow.token_mint, EscrowError::WrongMint);
+    require!(treasury_ta.mint == escrow.token_mint, EscrowError::WrongMint);
+    require!(treasury_ta.owner == treasury, EscrowError::NotAuthorized);
+
+    let amount = escrow_ta.amount;
+    require!(amount > 0, EscrowError::NoStuckFunds);
+
+    let lobby_id_hash = escrow.lobby_id_hash;
+    let bump = escrow.bump;
+
+    // PDA signer seeds for CPI
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, lobby_id_hash.as_ref(), &[bump]]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: escrow_ta.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: treasury_ta.to_account_info(),
+                authority: escrow.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(StuckFundsWithdrawn {
+        lobby_id_hash,
+        admin: ctx.accounts.admin.key(),
+        treasury,
+        amount,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,33 @@
 use anchor_lang::prelude::*;
 
+// anchor_lang re-exports `msg!` but not the compute-units log, so the
+// syscall is declared directly. Off-chain builds only log the checkpoint.
+#[cfg(all(feature = "profiling", target_os = "solana"))]
+extern "C" {
+    fn sol_log_compute_units_();
+}
+
+/// Logs the remaining compute units, tagged with the handler and checkpoint
+/// that emitted them, when built with the `profiling` feature. Compiles to
+/// nothing otherwise.
+macro_rules! profile {
+    ($handler:literal, $point:literal) => {
+        #[cfg(feature = "profiling")]
+        {
+            anchor_lang::prelude::msg!(concat!("profile ", $handler, ": ", $point));
+            #[cfg(target_os = "solana")]
+            unsafe {
+                crate::sol_log_compute_units_()
+            };
+        }
+    };
+}
+
 pub mod constants;
+#[cfg(feature = "cpi")]
+pub mod cpi_helpers;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -15,37 +41,335 @@ pub mod match_escrow {
 
     pub fn initialize_escrow(
         ctx: Context<InitializeEscrow>,
-        lobby_id_hash: [u8; 32],
-        host: Pubkey,
-        opponent: Pubkey,
-        wager_lamports: u64,
-        token_mint: Pubkey,
-        treasury: Pubkey,
+        params: InitializeEscrowParams,
     ) -> Result<()> {
-        instructions::initialize::handler(
-            ctx,
-            lobby_id_hash,
-            host,
-            opponent,
-            wager_lamports,
-            token_mint,
-            treasury,
-        )
+        instructions::initialize::handler(ctx, params)
     }
 
     pub fn deposit(ctx: Context<Deposit>) -> Result<()> {
         instructions::deposit::handler(ctx)
     }
 
-    pub fn settle(ctx: Context<Settle>, winner: Pubkey) -> Result<()> {
-        instructions::settle::handler(ctx, winner)
+    pub fn settle(ctx: Context<Settle>, winner: Pubkey, rent_to_winner: bool) -> Result<()> {
+        instructions::settle::handler(ctx, winner, rent_to_winner)
+    }
+
+    pub fn forfeit(
+        ctx: Context<Settle>,
+        forfeiter: Pubkey,
+        reason: u8,
+        rent_to_winner: bool,
+    ) -> Result<()> {
+        instructions::forfeit::handler(ctx, forfeiter, reason, rent_to_winner)
+    }
+
+    pub fn confirm_deposit(
+        ctx: Context<ConfirmDeposit>,
+        depositor: Pubkey,
+        payment_ref: [u8; 32],
+        confirm_nonce: u64,
+    ) -> Result<()> {
+        instructions::confirm_deposit::handler(ctx, depositor, payment_ref, confirm_nonce)
+    }
+
+    pub fn reclaim_unfunded(ctx: Context<ReclaimUnfunded>) -> Result<()> {
+        instructions::reclaim_unfunded::handler(ctx)
+    }
+
+    pub fn settle_draw(ctx: Context<SettleDraw>) -> Result<()> {
+        instructions::settle_draw::handler(ctx)
+    }
+
+    pub fn cancel_escrow(ctx: Context<CancelEscrow>) -> Result<()> {
+        instructions::cancel::handler(ctx)
+    }
+
+    pub fn claim_timeout_win(ctx: Context<Settle>, winner: Pubkey) -> Result<()> {
+        instructions::claim_timeout_win::handler(ctx, winner)
+    }
+
+    pub fn report_round(ctx: Context<ReportRound>, round_winner: Pubkey) -> Result<()> {
+        instructions::report_round::handler(ctx, round_winner)
+    }
+
+    pub fn settle_signed(ctx: Context<SettleSigned>, winner: Pubkey) -> Result<()> {
+        instructions::settle_signed::handler(ctx, winner)
+    }
+
+    pub fn raise_dispute(ctx: Context<RaiseDispute>) -> Result<()> {
+        instructions::dispute::raise_handler(ctx)
+    }
+
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>) -> Result<()> {
+        instructions::dispute::resolve_handler(ctx)
+    }
+
+    pub fn commit_result(ctx: Context<CommitResult>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commit_reveal::commit_handler(ctx, commitment)
+    }
+
+    pub fn reveal_result(
+        ctx: Context<RevealResult>,
+        winner: Pubkey,
+        nonce: [u8; 32],
+    ) -> Result<()> {
+        instructions::commit_reveal::reveal_handler(ctx, winner, nonce)
+    }
+
+    pub fn transfer_authority(
+        ctx: Context<TransferAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_authority::handler(ctx, new_authority)
+    }
+
+    pub fn quote_settlement(ctx: Context<QuoteSettlement>) -> Result<()> {
+        instructions::quote_settlement::handler(ctx)
+    }
+
+    pub fn refund_no_show(ctx: Context<RefundNoShow>, no_show_fee_bps: u16) -> Result<()> {
+        instructions::refund_no_show::handler(ctx, no_show_fee_bps)
+    }
+
+    pub fn sweep_closed(ctx: Context<SweepClosed>) -> Result<()> {
+        instructions::sweep_closed::handler(ctx)
+    }
+
+    pub fn initialize_multi_escrow(
+        ctx: Context<InitializeMultiEscrow>,
+        params: InitializeMultiEscrowParams,
+    ) -> Result<()> {
+        instructions::multi_escrow::initialize_handler(ctx, params)
+    }
+
+    pub fn deposit_multi(ctx: Context<DepositMulti>, player_index: u8) -> Result<()> {
+        instructions::multi_escrow::deposit_handler(ctx, player_index)
+    }
+
+    pub fn settle_multi(ctx: Context<SettleMulti>, winner: Pubkey) -> Result<()> {
+        instructions::multi_escrow::settle_handler(ctx, winner)
+    }
+
+    pub fn initialize_cross_escrow(
+        ctx: Context<InitializeCrossEscrow>,
+        params: InitializeCrossEscrowParams,
+    ) -> Result<()> {
+        instructions::cross_escrow::initialize_handler(ctx, params)
+    }
+
+    pub fn deposit_cross(ctx: Context<DepositCross>) -> Result<()> {
+        instructions::cross_escrow::deposit_handler(ctx)
+    }
+
+    pub fn settle_cross(ctx: Context<SettleCross>, winner: Pubkey) -> Result<()> {
+        instructions::cross_escrow::settle_handler(ctx, winner)
+    }
+
+    pub fn forfeit_cross(ctx: Context<SettleCross>, forfeiter: Pubkey) -> Result<()> {
+        instructions::cross_escrow::forfeit_handler(ctx, forfeiter)
+    }
+
+    pub fn settle_ranked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleRanked<'info>>,
+        finishers: Vec<Pubkey>,
+        payout_bps: Vec<u16>,
+    ) -> Result<()> {
+        instructions::settle_ranked::handler(ctx, finishers, payout_bps)
+    }
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>, treasury: Pubkey) -> Result<()> {
+        instructions::config::initialize_handler(ctx, treasury)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::config::set_paused_handler(ctx, paused)
+    }
+
+    pub fn set_treasury(ctx: Context<SetTreasury>, new_treasury: Pubkey) -> Result<()> {
+        instructions::config::set_treasury_handler(ctx, new_treasury)
+    }
+
+    pub fn rematch(
+        ctx: Context<Rematch>,
+        new_lobby_id_hash: [u8; 32],
+        deposit_deadline: i64,
+        match_deadline: i64,
+        settlement_deadline: i64,
+    ) -> Result<()> {
+        instructions::rematch::handler(
+            ctx,
+            new_lobby_id_hash,
+            deposit_deadline,
+            match_deadline,
+            settlement_deadline,
+        )
+    }
+
+    pub fn settle_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>,
+    ) -> Result<()> {
+        instructions::settle_batch::handler(ctx)
+    }
+
+    pub fn withdraw_stuck_funds(ctx: Context<WithdrawStuckFunds>) -> Result<()> {
+        instructions::withdraw_stuck_funds::handler(ctx)
+    }
+
+    pub fn migrate_escrow(ctx: Context<MigrateEscrow>) -> Result<()> {
+        instructions::migrate_escrow::handler(ctx)
+    }
+
+    pub fn auto_refund(ctx: Context<AutoRefund>) -> Result<()> {
+        instructions::auto_refund::handler(ctx)
+    }
+
+    pub fn update_wager(
+        ctx: Context<UpdateWager>,
+        host_wager: u64,
+        opponent_wager: u64,
+    ) -> Result<()> {
+        instructions::update_wager::handler(ctx, host_wager, opponent_wager)
+    }
+
+    pub fn settle_with_oracle_sig(
+        ctx: Context<SettleWithOracleSig>,
+        winner: Pubkey,
+    ) -> Result<()> {
+        instructions::settle_oracle::handler(ctx, winner)
+    }
+
+    pub fn settle_double_forfeit(
+        ctx: Context<SettleDoubleForfeit>,
+        abandonment_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::settle_double_forfeit::handler(ctx, abandonment_fee_bps)
+    }
+
+    pub fn deposit_both(ctx: Context<DepositBoth>) -> Result<()> {
+        instructions::deposit_both::handler(ctx)
+    }
+
+    pub fn settle_pending(ctx: Context<SettlePending>, winner: Pubkey) -> Result<()> {
+        instructions::pending_payout::settle_pending_handler(ctx, winner)
+    }
+
+    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+        instructions::pending_payout::claim_handler(ctx)
+    }
+
+    pub fn settle_with_challenge(
+        ctx: Context<SettleWithChallenge>,
+        winner: Pubkey,
+    ) -> Result<()> {
+        instructions::challenge::propose_handler(ctx, winner)
+    }
+
+    pub fn finalize_settle(ctx: Context<Settle>) -> Result<()> {
+        instructions::challenge::finalize_handler(ctx)
+    }
+
+    pub fn expire_refund(ctx: Context<ExpireRefund>) -> Result<()> {
+        instructions::expire_refund::handler(ctx)
+    }
+
+    pub fn initialize_escrow_spl(
+        ctx: Context<InitializeEscrowSpl>,
+        params: InitializeEscrowParams,
+    ) -> Result<()> {
+        instructions::initialize::spl_handler(ctx, params)
+    }
+
+    pub fn add_bonus(ctx: Context<AddBonus>, amount: u64) -> Result<()> {
+        instructions::add_bonus::handler(ctx, amount)
+    }
+
+    pub fn require_funded(ctx: Context<RequireFunded>) -> Result<()> {
+        instructions::require_funded::handler(ctx)
+    }
+
+    pub fn get_state(ctx: Context<GetState>) -> Result<EscrowSnapshot> {
+        instructions::get_state::handler(ctx)
+    }
+
+    pub fn initialize_result_log(ctx: Context<InitializeResultLog>) -> Result<()> {
+        instructions::result_log::initialize_handler(ctx)
+    }
+
+    pub fn join(ctx: Context<Deposit>) -> Result<()> {
+        instructions::join::handler(ctx)
+    }
+
+    pub fn settle_tiebreak(ctx: Context<Settle>) -> Result<()> {
+        instructions::settle_tiebreak::handler(ctx)
+    }
+
+    pub fn settle_agreed(
+        ctx: Context<SettleAgreed>,
+        host_bps: u16,
+        opponent_bps: u16,
+    ) -> Result<()> {
+        instructions::settle_agreed::handler(ctx, host_bps, opponent_bps)
+    }
+
+    pub fn mutual_cancel(ctx: Context<MutualCancel>) -> Result<()> {
+        instructions::mutual_cancel::handler(ctx)
+    }
+
+    pub fn emergency_refund(ctx: Context<EmergencyRefund>) -> Result<()> {
+        instructions::emergency_refund::handler(ctx)
+    }
+
+    pub fn deposit_installment(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        instructions::deposit::installment_handler(ctx, amount)
+    }
+
+    pub fn initialize_fee_vault(ctx: Context<InitializeFeeVault>) -> Result<()> {
+        instructions::fee_vault::initialize_handler(ctx)
+    }
+
+    pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
+        instructions::fee_vault::sweep_handler(ctx)
+    }
+
+    pub fn initialize_player_index(ctx: Context<InitializePlayerIndex>, page: u8) -> Result<()> {
+        instructions::player_index::initialize_handler(ctx, page)
+    }
+
+    pub fn remove_from_player_index(
+        ctx: Context<RemoveFromPlayerIndex>,
+        lobby_id_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::player_index::remove_handler(ctx, lobby_id_hash)
+    }
+
+    pub fn initialize_series_escrow(
+        ctx: Context<InitializeSeriesEscrow>,
+        params: InitializeSeriesEscrowParams,
+    ) -> Result<()> {
+        instructions::series_escrow::initialize_handler(ctx, params)
+    }
+
+    pub fn deposit_series(ctx: Context<DepositSeries>) -> Result<()> {
+        instructions::series_escrow::deposit_handler(ctx)
+    }
+
+    pub fn report_series_round(
+        ctx: Context<ReportSeriesRound>,
+        round: u8,
+        round_winner: Pubkey,
+    ) -> Result<()> {
+        instructions::series_escrow::report_round_handler(ctx, round, round_winner)
+    }
+
+    pub fn settle_series(ctx: Context<SettleSeries>) -> Result<()> {
+        instructions::series_escrow::settle_handler(ctx)
     }
 
-    pub fn forfeit(ctx: Context<Forfeit>, forfeiter: Pubkey) -> Result<()> {
-        instructions::forfeit::handler(ctx, forfeiter)
+    pub fn crank_settle(ctx: Context<CrankSettle>) -> Result<()> {
+        instructions::crank_settle::handler(ctx)
     }
 
-    pub fn confirm_deposit(ctx: Context<ConfirmDeposit>, depositor: Pubkey) -> Result<()> {
-        instructions::confirm_deposit::handler(ctx, depositor)
+    pub fn sponsor_deposit(ctx: Context<SponsorDeposit>) -> Result<()> {
+        instructions::sponsor_deposit::handler(ctx)
     }
 }
@@ -1,17 +1,42 @@
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::constants::{ESCROW_SEED, PLAYER_INDEX_CAPACITY, RESULT_LOG_CAPACITY};
+use crate::error::EscrowError;
+
+/// Escrow PDA seed for one match of a lobby: `sha256(lobby_id || nonce)`,
+/// with the nonce little-endian.
+///
+/// Escrows close on settle, so seeding with a bare `sha256(lobby_id)` lets
+/// the next match of the same lobby land on the same address while indexers
+/// may still be processing the last one. Folding a per-match nonce into the
+/// hash gives every match a fresh PDA with no extra on-chain state. A
+/// used-hash registry or a post-settle cooldown would also work, but both
+/// need an account that outlives the escrow, paying rent for every match
+/// ever played.
+pub fn lobby_seed(lobby_id: &[u8], nonce: u64) -> [u8; 32] {
+    hashv(&[lobby_id, &nonce.to_le_bytes()]).to_bytes()
+}
 
 #[account]
 #[derive(InitSpace)]
 pub struct MatchEscrow {
-    /// SHA-256 hash of the Convex lobby ID string
+    /// PDA seed identifying the match; see [`lobby_seed`]
     pub lobby_id_hash: [u8; 32],
     /// Host player wallet
     pub host: Pubkey,
     /// Opponent player wallet
     pub opponent: Pubkey,
-    /// Per-player wager in atomic units (lamports or token base units)
-    pub wager_lamports: u64,
-    /// Token mint: Pubkey::default() = native SOL, otherwise SPL mint (e.g. USDC)
+    /// Host stake in atomic units (lamports or token base units)
+    pub host_wager: u64,
+    /// Opponent stake in atomic units (lamports or token base units)
+    pub opponent_wager: u64,
+    /// Token mint: Pubkey::default() = native SOL, otherwise SPL mint (e.g. USDC).
+    ///
+    /// Wallets that hold plain SOL should use native mode: it needs no token
+    /// accounts and is cheapest. Clients that already route every stake
+    /// through the token program can use the WSOL mint (`So111...112`)
+    /// instead; they wrap with `sync_native` before depositing, and `settle`
+    /// can unwrap the payout when the winner token account is omitted.
     pub token_mint: Pubkey,
     /// Platform treasury wallet for fee collection
     pub treasury: Pubkey,
@@ -25,11 +50,1077 @@ pub struct MatchEscrow {
     pub settled: bool,
     /// PDA bump seed
     pub bump: u8,
+    /// Unix timestamp after which deposits are rejected and a lone deposit can be reclaimed
+    pub deposit_deadline: i64,
+    /// Treasury fee in basis points applied to the pot at settlement
+    pub fee_bps: u16,
+    /// Unix timestamp after which the authority may award the pot to a surviving player
+    pub match_deadline: i64,
+    /// Round wins needed to take the series (1 = single game)
+    pub rounds_to_win: u8,
+    /// Rounds won by the host so far
+    pub host_wins: u8,
+    /// Rounds won by the opponent so far
+    pub opponent_wins: u8,
+    /// Whether payouts are frozen pending review of a contested result
+    pub disputed: bool,
+    /// sha256(winner || nonce) committed by a player; all zeroes when unset
+    pub result_commitment: [u8; 32],
+    /// Player who submitted `result_commitment`
+    pub result_committer: Pubkey,
+    /// Unix timestamp after which the committed result can no longer be revealed
+    pub reveal_deadline: i64,
+    /// Whether either wager may be zero (sponsored/free-entry matches)
+    pub free_entry: bool,
+    /// Unix timestamp the pot was paid out; 0 until settled
+    pub settled_at: i64,
+    /// Player the pot was awarded to; Pubkey::default() for draws or until settled
+    pub winner: Pubkey,
+    /// Keep the account open after settlement so the outcome stays readable until swept
+    pub retain_record: bool,
+    /// x402 settlement hash that justified the host's confirmed deposit; all zeroes when unset
+    pub host_payment_ref: [u8; 32],
+    /// x402 settlement hash that justified the opponent's confirmed deposit; all zeroes when unset
+    pub opponent_payment_ref: [u8; 32],
+    /// When true, the slot deadlines below are authoritative and the unix
+    /// timestamp deadlines are ignored
+    pub slot_deadlines: bool,
+    /// Slot after which deposits are rejected (slot mode only)
+    pub deposit_deadline_slot: u64,
+    /// Slot after which the authority may award a timeout win (slot mode only)
+    pub match_deadline_slot: u64,
+    /// Send `settle`/`forfeit` fees to `ProgramConfig::treasury` instead of `treasury`
+    pub use_global_treasury: bool,
+    /// Closed-beta mode: `deposit` also requires the authority's signature
+    pub require_authorized_deposit: bool,
+    /// Wallet that paid the init rent; `settle`/`forfeit` return it here on close
+    pub rent_payer: Pubkey,
+    /// Informational backend reference (e.g. a Convex match ID hash); all zeroes when unset
+    pub match_ref: [u8; 32],
+    /// Account layout version (`ESCROW_VERSION`); 0 for accounts that predate versioning
+    pub version: u8,
+    /// Set (and persisted) before a payout's first CPI, cleared when it completes
+    pub settling: bool,
+    /// Unix timestamp after which a funded but unsettled escrow can be refunded by anyone
+    pub settlement_deadline: i64,
+    /// Base units the host has actually put into the escrow
+    pub host_deposited_amount: u64,
+    /// Base units the opponent has actually put into the escrow
+    pub opponent_deposited_amount: u64,
+    /// Result oracle allowed to name the winner via `settle_with_oracle_sig`
+    /// (default pubkey = none)
+    pub oracle: Pubkey,
+    /// Affiliate paid a cut of the fee by `settle`/`forfeit`
+    /// (default pubkey = none)
+    pub referrer: Pubkey,
+    /// Referrer's cut in basis points of the pot, carved out of `fee_bps`
+    pub referral_bps: u16,
+    /// `RoundingMode` applied to the fee, stored as its `u8` discriminant
+    pub rounding: u8,
+    /// Payout recorded by `settle_pending`, awaiting `claim_winnings`
+    pub pending_payout: u64,
+    /// Verified Metaplex collection a depositor must hold an NFT from
+    /// (default pubkey = ungated)
+    pub required_collection: Pubkey,
+    /// Signer allowed to `settle`/`forfeit`; may differ from `authority` so
+    /// a game server can settle without controlling escrow administration
+    pub settle_authority: Pubkey,
+    /// Winner named by `settle_with_challenge`, paid by `finalize_settle`
+    /// (default pubkey = no proposal)
+    pub proposed_winner: Pubkey,
+    /// Unix timestamp until which `proposed_winner` can be disputed
+    pub challenge_deadline: i64,
+    /// Unix timestamp after which anyone can refund all deposits via
+    /// `expire_refund`, regardless of the escrow's other state
+    pub expires_at: i64,
+    /// Third-party contributions from `add_bonus`, paid out with the pot
+    pub bonus_pool: u64,
+    /// Share of the pot in basis points burned out of the fee (SPL only)
+    pub burn_bps: u16,
+    /// Lifecycle stage (`EscrowStatus` discriminant), moved only through
+    /// `transition`. The deposit/settled/disputed flags are still kept in
+    /// step for existing readers.
+    pub status: u8,
+    /// Whether the winner has collected `pending_payout` via `claim_winnings`
+    pub payout_claimed: bool,
+    /// Fee floor in base units, applied when `fee_bps` of the pot is
+    /// smaller; capped at half the pot
+    pub min_fee: u64,
+    /// Lets `treasury` be one of the players; `join` honours it for the
+    /// joiner the init-time check could not see
+    pub allow_player_treasury: bool,
+    /// Player whose deposit landed first; `settle_tiebreak` pays them
+    /// (default pubkey = no deposit yet, or recorded before v19)
+    pub first_depositor: Pubkey,
+    /// Fee ceiling in base units, applied after `min_fee`; 0 = no cap
+    pub max_fee: u64,
+    /// Highest `confirm_nonce` accepted by `confirm_deposit`
+    pub last_confirm_nonce: u64,
+    /// Unix timestamp at which the second deposit landed (0 = not funded)
+    pub funded_at: i64,
+    /// Seconds after `funded_at` before a winner can be paid; 0 or less = no minimum
+    pub min_match_duration: i64,
+    /// Player `crank_settle` pays once `settlement_deadline` passes without
+    /// a result (default pubkey = none; `auto_refund` applies instead)
+    pub default_winner: Pubkey,
+    /// Share of the fee, in basis points, paid to whoever runs `crank_settle`
+    pub crank_reward_bps: u16,
+    /// Matchmaking mode: `join` must be co-signed by the authority, which
+    /// vets whoever takes the open seat
+    pub require_join_approval: bool,
+    /// Platform-funded free roll: the authority stakes both seats via
+    /// `sponsor_deposit`, players cannot deposit, and no fee is taken. The
+    /// stake never goes back to the players: the refund paths reject it, so
+    /// it leaves only through settlement
+    pub sponsored: bool,
+    /// How the host's seat is being paid (`DepositMethod` discriminant)
+    pub host_deposit_method: u8,
+    /// How the opponent's seat is being paid (`DepositMethod` discriminant)
+    pub opponent_deposit_method: u8,
+}
+
+/// How the fee is rounded to whole base units. The winner always receives
+/// the pot minus the rounded fee, so the modes differ only in who keeps the
+/// fractional unit.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round the fee down; the winner keeps the remainder (default)
+    Floor = 0,
+    /// Round the fee up; the house keeps the remainder
+    Ceil = 1,
+    /// Round half to even (banker's rounding)
+    HalfEven = 2,
+}
+
+impl RoundingMode {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Floor),
+            1 => Some(Self::Ceil),
+            2 => Some(Self::HalfEven),
+            _ => None,
+        }
+    }
+
+    /// `amount * bps / 10_000`, rounded per this mode.
+    pub fn apply_bps(self, amount: u64, bps: u16) -> Result<u64> {
+        let scaled = (amount as u128)
+            .checked_mul(bps as u128)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        let quotient = scaled / 10_000;
+        let remainder = scaled % 10_000;
+        let round_up = match self {
+            Self::Floor => false,
+            Self::Ceil => remainder > 0,
+            Self::HalfEven => remainder > 5_000 || (remainder == 5_000 && quotient & 1 == 1),
+        };
+        u64::try_from(quotient + round_up as u128)
+            .map_err(|_| error!(EscrowError::InsufficientFunds))
+    }
+}
+
+/// Why a player forfeited, recorded on `EscrowForfeited` for analytics.
+/// Passed to `forfeit` as its `u8` discriminant.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ForfeitReason {
+    Disconnect = 0,
+    Cheating = 1,
+    Surrender = 2,
+    Timeout = 3,
+}
+
+impl ForfeitReason {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Disconnect),
+            1 => Some(Self::Cheating),
+            2 => Some(Self::Surrender),
+            3 => Some(Self::Timeout),
+            _ => None,
+        }
+    }
+}
+
+/// Lifecycle of a `MatchEscrow`, stored in `status` as its `u8`
+/// discriminant. `Settled` and `Refunded` are terminal.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EscrowStatus {
+    Created = 0,
+    PartiallyFunded = 1,
+    Funded = 2,
+    Settled = 3,
+    Refunded = 4,
+    Disputed = 5,
+}
+
+impl EscrowStatus {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Created),
+            1 => Some(Self::PartiallyFunded),
+            2 => Some(Self::Funded),
+            3 => Some(Self::Settled),
+            4 => Some(Self::Refunded),
+            5 => Some(Self::Disputed),
+            _ => None,
+        }
+    }
+
+    /// Whether an escrow in this stage may move to `to`. Only funded
+    /// matches can be disputed, and a dispute ends either back in
+    /// `Funded` (resolved) or `Refunded` (expired or mutually cancelled).
+    pub fn can_transition_to(self, to: Self) -> bool {
+        use EscrowStatus::*;
+        matches!(
+            (self, to),
+            (Created, PartiallyFunded | Funded | Refunded)
+                | (PartiallyFunded, Funded | Refunded)
+                | (Funded, Settled | Refunded | Disputed)
+                | (Disputed, Funded | Refunded)
+        )
+    }
+}
+
+/// How a seat is paid: on-chain via `deposit`/`deposit_installment`
+/// (or `deposit_both`/`sponsor_deposit`), or off-chain via x402 and
+/// `confirm_deposit`. Stored per seat as the `u8` discriminant; once set,
+/// a seat can only be paid the same way.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DepositMethod {
+    None = 0,
+    OnChain = 1,
+    Confirmed = 2,
 }
 
 impl MatchEscrow {
+    /// Escrow PDA and bump for `lobby_id_hash`. The single place the seeds
+    /// are spelled out for code that can't use a `seeds` constraint.
+    pub fn pda(lobby_id_hash: &[u8; 32], program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ESCROW_SEED, lobby_id_hash.as_ref()], program_id)
+    }
+
+    /// Combined stake of both players. Errors on overflow.
+    pub fn total_wager(&self) -> Result<u64> {
+        self.host_wager
+            .checked_add(self.opponent_wager)
+            .ok_or_else(|| error!(EscrowError::InsufficientFunds))
+    }
+
+    /// Whether `key` holds either seat.
+    pub fn is_player(&self, key: &Pubkey) -> bool {
+        *key == self.host || *key == self.opponent
+    }
+
+    /// Whether both players have deposited.
+    pub fn both_funded(&self) -> bool {
+        self.host_deposited && self.opponent_deposited
+    }
+
+    /// The player across the table from `key`, or `None` if `key` is not
+    /// a player.
+    pub fn other_player(&self, key: &Pubkey) -> Option<Pubkey> {
+        if *key == self.host {
+            Some(self.opponent)
+        } else if *key == self.opponent {
+            Some(self.host)
+        } else {
+            None
+        }
+    }
+
+    /// Records that a seat is paid via `method`, rejecting a switch from the
+    /// other method so an on-chain installment and an x402 confirmation are
+    /// never both counted toward the same wager.
+    pub fn set_deposit_method(&mut self, is_host: bool, method: DepositMethod) -> Result<()> {
+        let current = if is_host {
+            &mut self.host_deposit_method
+        } else {
+            &mut self.opponent_deposit_method
+        };
+        require!(
+            *current == DepositMethod::None as u8 || *current == method as u8,
+            EscrowError::DepositMethodMismatch
+        );
+        *current = method as u8;
+        Ok(())
+    }
+
+    /// What both players have actually deposited. Errors on overflow.
+    pub fn total_deposited(&self) -> Result<u64> {
+        self.host_deposited_amount
+            .checked_add(self.opponent_deposited_amount)
+            .ok_or_else(|| error!(EscrowError::InsufficientFunds))
+    }
+
+    /// Everything a payout distributes: both deposits plus `bonus_pool`.
+    pub fn total_pot(&self) -> Result<u64> {
+        self.total_deposited()?
+            .checked_add(self.bonus_pool)
+            .ok_or_else(|| error!(EscrowError::InsufficientFunds))
+    }
+
+    /// What a full refund returns to `(host, opponent)`: each deposit plus a
+    /// share of `bonus_pool` in proportion to it (evenly when neither side
+    /// staked anything). The host's share rounds down; the opponent gets the
+    /// remainder so the whole pool is returned.
+    pub fn refund_amounts(&self) -> Result<(u64, u64)> {
+        let deposited = self.total_deposited()?;
+        let host_bonus = if deposited == 0 {
+            self.bonus_pool / 2
+        } else {
+            ((self.bonus_pool as u128) * (self.host_deposited_amount as u128)
+                / (deposited as u128)) as u64
+        };
+        let opponent_bonus = self.bonus_pool - host_bonus;
+        let host = self
+            .host_deposited_amount
+            .checked_add(host_bonus)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        let opponent = self
+            .opponent_deposited_amount
+            .checked_add(opponent_bonus)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        Ok((host, opponent))
+    }
+
+    /// Winner-takes-all split of the pot (deposits plus bonus) as `(total, fee, payout)`:
+    /// `fee_bps` of the total (rounded per `rounding`, raised to `min_fee`,
+    /// capped at `max_fee`) to the treasury, the rest to the winner. All arithmetic is checked,
+    /// with a u128 intermediate for the fee.
+    pub fn compute_distribution(&self) -> Result<(u64, u64, u64)> {
+        let total = self.total_pot()?;
+        // Unknown modes are rejected at init; fall back to floor regardless
+        let rounding = RoundingMode::from_u8(self.rounding).unwrap_or(RoundingMode::Floor);
+        // The floor never takes more than half the pot, so a tiny wager
+        // still leaves the winner something
+        let floor = self.min_fee.min(total / 2);
+        let mut fee = rounding.apply_bps(total, self.fee_bps)?.max(floor);
+        // Whale protection: 0 means uncapped
+        if self.max_fee > 0 {
+            fee = fee.min(self.max_fee);
+        }
+        let payout = total
+            .checked_sub(fee)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        Ok((total, fee, payout))
+    }
+
+    /// Splits a settlement `fee` on a pot of `total` into
+    /// `(referral, burn, treasury_fee)`. The referrer's and burn shares are
+    /// carved out of the fee; whatever is left goes to the treasury.
+    pub fn split_fee(&self, total: u64, fee: u64) -> Result<(u64, u64, u64)> {
+        let share = |bps: u16| -> Result<u64> {
+            u64::try_from(
+                (total as u128)
+                    .checked_mul(bps as u128)
+                    .ok_or(EscrowError::InsufficientFunds)?
+                    / 10_000,
+            )
+            .map_err(|_| error!(EscrowError::InsufficientFunds))
+        };
+        let referral = if self.referrer == Pubkey::default() {
+            0
+        } else {
+            share(self.referral_bps)?
+        };
+        let burn = share(self.burn_bps)?;
+        // A `max_fee` cap can leave less fee than the carve-outs; the
+        // referrer is paid first, then the burn, and the treasury keeps
+        // whatever remains
+        let referral = referral.min(fee);
+        let burn = burn.min(fee - referral);
+        let treasury_fee = fee
+            .checked_sub(referral)
+            .and_then(|v| v.checked_sub(burn))
+            .ok_or(EscrowError::InsufficientFunds)?;
+        Ok((referral, burn, treasury_fee))
+    }
+
+    /// Whether deposits are still accepted at `clock`.
+    ///
+    /// Unix timestamps track wall-clock time but are validator-reported and
+    /// can drift; slots are monotonic but their duration varies with network
+    /// conditions. Each escrow picks one mode at init and only that deadline
+    /// is enforced.
+    pub fn deposit_window_open(&self, clock: &Clock) -> bool {
+        if self.slot_deadlines {
+            clock.slot <= self.deposit_deadline_slot
+        } else {
+            clock.unix_timestamp <= self.deposit_deadline
+        }
+    }
+
+    /// Whether the match deadline has passed at `clock`, in the escrow's
+    /// deadline mode.
+    pub fn match_deadline_passed(&self, clock: &Clock) -> bool {
+        if self.slot_deadlines {
+            clock.slot > self.match_deadline_slot
+        } else {
+            clock.unix_timestamp > self.match_deadline
+        }
+    }
+
+    /// Marks a payout as in progress, failing if one already is. Callers
+    /// persist the flag with `exit` before their first CPI, so a payout
+    /// re-entered from that CPI is rejected here.
+    pub fn begin_settling(&mut self) -> Result<()> {
+        require!(!self.settling, EscrowError::SettlementInProgress);
+        self.settling = true;
+        Ok(())
+    }
+
+    /// Whether the escrow has outlived `expires_at`, after which anyone
+    /// can `expire_refund` it. The expiry second itself still counts as live.
+    pub fn expired(&self, clock: &Clock) -> bool {
+        clock.unix_timestamp > self.expires_at
+    }
+
     /// Returns true if this escrow uses native SOL (not an SPL token).
     pub fn is_native_sol(&self) -> bool {
         self.token_mint == Pubkey::default()
     }
+
+    /// Rejects a payout to `winner` that would bypass a pending
+    /// `settle_with_challenge` proposal: only the proposed winner can be
+    /// paid, and only once the challenge window has closed.
+    pub fn check_challenge(&self, winner: &Pubkey, clock: &Clock) -> Result<()> {
+        if self.proposed_winner == Pubkey::default() {
+            return Ok(());
+        }
+        require!(self.proposed_winner == *winner, EscrowError::InvalidWinner);
+        require!(
+            clock.unix_timestamp > self.challenge_deadline,
+            EscrowError::ChallengeWindowOpen
+        );
+        Ok(())
+    }
+
+    /// Rejects a payout before `min_match_duration` has passed since the
+    /// escrow was funded, so a result can't be posted the moment both
+    /// stakes land. Applies to every payout the settle authority posts,
+    /// forfeits included; only co-signed settlements skip it, since both
+    /// players attest to the result.
+    pub fn check_min_duration(&self, clock: &Clock) -> Result<()> {
+        if self.min_match_duration <= 0 {
+            return Ok(());
+        }
+        let earliest = self
+            .funded_at
+            .checked_add(self.min_match_duration)
+            .ok_or(EscrowError::MatchTooShort)?;
+        require!(
+            clock.unix_timestamp >= earliest,
+            EscrowError::MatchTooShort
+        );
+        Ok(())
+    }
+
+    /// Current lifecycle stage. Unknown values read as `Created`, which
+    /// `migrate_escrow` corrects from the flags.
+    pub fn escrow_status(&self) -> EscrowStatus {
+        EscrowStatus::from_u8(self.status).unwrap_or(EscrowStatus::Created)
+    }
+
+    /// Stage implied by the deposit flags alone.
+    pub fn funding_status(&self) -> EscrowStatus {
+        match (self.host_deposited, self.opponent_deposited) {
+            (true, true) => EscrowStatus::Funded,
+            (false, false) => EscrowStatus::Created,
+            _ => EscrowStatus::PartiallyFunded,
+        }
+    }
+
+    /// Moves the escrow to `to`, rejecting transitions the lifecycle does
+    /// not allow.
+    pub fn transition(&mut self, to: EscrowStatus) -> Result<()> {
+        require!(
+            self.escrow_status().can_transition_to(to),
+            EscrowError::InvalidStateTransition
+        );
+        self.status = to as u8;
+        Ok(())
+    }
+}
+
+/// Free-for-all pot for multiplayer pods. Every player stakes the same
+/// `wager`; the 1v1 `MatchEscrow` is unaffected.
+#[account]
+#[derive(InitSpace)]
+pub struct MultiMatchEscrow {
+    /// SHA-256 hash of the Convex lobby ID string
+    pub lobby_id_hash: [u8; 32],
+    /// Seated player wallets, in seat order
+    #[max_len(8)]
+    pub players: Vec<Pubkey>,
+    /// Whether the player in the same seat has deposited
+    #[max_len(8)]
+    pub deposited: Vec<bool>,
+    /// Per-player stake in atomic units (lamports or token base units)
+    pub wager: u64,
+    /// Token mint: Pubkey::default() = native SOL, otherwise SPL mint
+    pub token_mint: Pubkey,
+    /// Platform treasury wallet for fee collection
+    pub treasury: Pubkey,
+    /// Server-controlled signer (only authority can settle)
+    pub authority: Pubkey,
+    /// Whether the escrow has been settled
+    pub settled: bool,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Unix timestamp after which deposits are rejected
+    pub deposit_deadline: i64,
+    /// Treasury fee in basis points applied to the pot at settlement
+    pub fee_bps: u16,
+}
+
+impl MultiMatchEscrow {
+    /// Combined stake of every seated player. Errors on overflow.
+    pub fn total_pot(&self) -> Result<u64> {
+        self.wager
+            .checked_mul(self.players.len() as u64)
+            .ok_or_else(|| error!(EscrowError::InsufficientFunds))
+    }
+
+    /// Returns true once every seated player has deposited.
+    pub fn fully_funded(&self) -> bool {
+        self.deposited.iter().all(|d| *d)
+    }
+
+    /// Returns true if this escrow uses native SOL (not an SPL token).
+    pub fn is_native_sol(&self) -> bool {
+        self.token_mint == Pubkey::default()
+    }
+}
+
+/// Best-of-N pot where every round carries its own stake. Each player
+/// deposits the sum of `round_wagers`; each reported round moves both
+/// players' stake for that round to the round winner's tally, and
+/// settlement pays the tallies out less the fee. Native SOL only. The
+/// 1v1 `MatchEscrow` is unaffected.
+///
+/// Compute: rounds are capped at `MAX_SERIES_ROUNDS`, so summing the stakes
+/// is one pass over at most nine values and reporting a round is constant
+/// time; no handler's cost grows beyond that bound.
+#[account]
+#[derive(InitSpace)]
+pub struct SeriesEscrow {
+    /// SHA-256 hash of the Convex lobby ID string
+    pub lobby_id_hash: [u8; 32],
+    /// Host player wallet
+    pub host: Pubkey,
+    /// Opponent player wallet
+    pub opponent: Pubkey,
+    /// Platform treasury wallet for fee collection
+    pub treasury: Pubkey,
+    /// Server-controlled signer (reports rounds and settles)
+    pub authority: Pubkey,
+    /// Per-player stake of each round in lamports, in play order
+    #[max_len(9)]
+    pub round_wagers: Vec<u64>,
+    /// Winner of each round reported so far, in play order: 1 = host,
+    /// 2 = opponent
+    #[max_len(9)]
+    pub round_results: Vec<u8>,
+    /// Stakes won by the host so far, in lamports
+    pub host_tally: u64,
+    /// Stakes won by the opponent so far, in lamports
+    pub opponent_tally: u64,
+    pub host_deposited: bool,
+    pub opponent_deposited: bool,
+    /// Whether the escrow has been settled
+    pub settled: bool,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Unix timestamp after which deposits are rejected
+    pub deposit_deadline: i64,
+    /// Treasury fee in basis points applied to each tally at settlement
+    pub fee_bps: u16,
+}
+
+impl SeriesEscrow {
+    /// What each player deposits: the sum of every round's stake. Errors on
+    /// overflow.
+    pub fn stake_per_player(&self) -> Result<u64> {
+        self.round_wagers.iter().try_fold(0u64, |sum, wager| {
+            sum.checked_add(*wager)
+                .ok_or_else(|| error!(EscrowError::InsufficientFunds))
+        })
+    }
+
+    /// Returns true once both players have deposited.
+    pub fn both_funded(&self) -> bool {
+        self.host_deposited && self.opponent_deposited
+    }
+
+    /// Returns true once every staked round has a reported winner.
+    pub fn all_rounds_played(&self) -> bool {
+        self.round_results.len() == self.round_wagers.len()
+    }
+}
+
+/// Two-mint pot for cross-asset challenges: each player stakes in their
+/// own asset (either may be native SOL) and the winner receives both
+/// stakes in their original tokens. The single-mint `MatchEscrow` is
+/// unaffected.
+#[account]
+#[derive(InitSpace)]
+pub struct CrossAssetEscrow {
+    /// SHA-256 hash of the Convex lobby ID string
+    pub lobby_id_hash: [u8; 32],
+    /// Host player wallet
+    pub host: Pubkey,
+    /// Opponent player wallet
+    pub opponent: Pubkey,
+    /// Mint of the host's stake: Pubkey::default() = native SOL
+    pub host_mint: Pubkey,
+    /// Mint of the opponent's stake: Pubkey::default() = native SOL
+    pub opponent_mint: Pubkey,
+    /// Host stake in base units of `host_mint`
+    pub host_wager: u64,
+    /// Opponent stake in base units of `opponent_mint`
+    pub opponent_wager: u64,
+    /// Whether the host has deposited their wager
+    pub host_deposited: bool,
+    /// Whether the opponent has deposited their wager
+    pub opponent_deposited: bool,
+    /// Platform treasury wallet for fee collection
+    pub treasury: Pubkey,
+    /// Server-controlled signer (only authority can settle)
+    pub authority: Pubkey,
+    /// Whether the escrow has been settled
+    pub settled: bool,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Unix timestamp after which deposits are rejected
+    pub deposit_deadline: i64,
+    /// Treasury fee in basis points, taken from each stake in its own asset
+    pub fee_bps: u16,
+}
+
+impl CrossAssetEscrow {
+    /// Whether both players have deposited.
+    pub fn both_funded(&self) -> bool {
+        self.host_deposited && self.opponent_deposited
+    }
+
+    /// The player across the table from `key`, or `None` if `key` is not
+    /// a player.
+    pub fn other_player(&self, key: &Pubkey) -> Option<Pubkey> {
+        if *key == self.host {
+            Some(self.opponent)
+        } else if *key == self.opponent {
+            Some(self.host)
+        } else {
+            None
+        }
+    }
+
+    /// Splits one stake into `(fee, payout)`: `fee_bps` of the stake,
+    /// rounded down, in the stake's own asset.
+    pub fn split_stake(&self, stake: u64) -> Result<(u64, u64)> {
+        let fee = RoundingMode::Floor.apply_bps(stake, self.fee_bps)?;
+        let payout = stake
+            .checked_sub(fee)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        Ok((fee, payout))
+    }
+}
+
+/// One settlement recorded in a `ResultLog`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct ResultEntry {
+    pub lobby_id_hash: [u8; 32],
+    pub winner: Pubkey,
+    /// Payout to the winner in base units
+    pub amount: u64,
+    /// Unix timestamp of the settlement
+    pub timestamp: i64,
+}
+
+/// Append-only history of settlements whose fee went to `treasury`, stored
+/// at `[RESULT_LOG_SEED, treasury]`. It outlives the escrows it records, so
+/// a lobby that was closed and re-created under the same hash cannot pass
+/// off a new result as its first. Only the latest `RESULT_LOG_CAPACITY`
+/// entries are kept on-chain; `ResultLogged` events carry the full history.
+#[account]
+#[derive(InitSpace)]
+pub struct ResultLog {
+    pub treasury: Pubkey,
+    /// Results ever recorded; the next one lands in slot
+    /// `count % RESULT_LOG_CAPACITY`
+    pub count: u64,
+    pub entries: [ResultEntry; RESULT_LOG_CAPACITY],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ResultLog {
+    /// Appends `entry`, overwriting the oldest once the buffer is full.
+    /// Returns the entry's sequence number.
+    pub fn record(&mut self, entry: ResultEntry) -> u64 {
+        let sequence = self.count;
+        self.entries[(sequence % RESULT_LOG_CAPACITY as u64) as usize] = entry;
+        self.count = sequence.saturating_add(1);
+        sequence
+    }
+
+    /// Whether a result for `lobby_id_hash` is still in the buffer.
+    pub fn contains(&self, lobby_id_hash: &[u8; 32]) -> bool {
+        let stored = self.count.min(RESULT_LOG_CAPACITY as u64) as usize;
+        self.entries[..stored]
+            .iter()
+            .any(|entry| entry.lobby_id_hash == *lobby_id_hash)
+    }
+}
+
+/// Program-wide settings, stored in a singleton PDA at `[CONFIG_SEED]`.
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    /// Key allowed to change program-wide settings
+    pub admin: Pubkey,
+    /// Circuit breaker: when true, new escrows and deposits are rejected.
+    /// Settlement paths stay open so in-flight matches can resolve.
+    pub paused: bool,
+    /// Fee destination for escrows created with `use_global_treasury`
+    pub treasury: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Holds settlement fees for `treasury` until `sweep_fees` moves them out
+/// in bulk, stored at `[FEE_VAULT_SEED, treasury]`. Native fees sit in the
+/// vault's own lamports above rent; SPL fees in token accounts it owns.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    pub treasury: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// One page of a wallet's active lobbies, stored at
+/// `[PLAYER_INDEX_SEED, player, page]` so the lobby UI can find a player's
+/// matches without scanning program accounts. Deposits add the lobby and
+/// settlements remove it when the page is passed; a wallet in more than
+/// `PLAYER_INDEX_CAPACITY` matches opens another page.
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerIndex {
+    pub player: Pubkey,
+    pub page: u8,
+    /// Lobby hashes of escrows the player has money in, in no fixed order
+    #[max_len(16)]
+    pub lobbies: Vec<[u8; 32]>,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl PlayerIndex {
+    /// Adds `lobby_id_hash` unless it is already listed.
+    pub fn add(&mut self, lobby_id_hash: [u8; 32]) -> Result<()> {
+        if self.lobbies.contains(&lobby_id_hash) {
+            return Ok(());
+        }
+        require!(
+            self.lobbies.len() < PLAYER_INDEX_CAPACITY,
+            EscrowError::PlayerIndexFull
+        );
+        self.lobbies.push(lobby_id_hash);
+        Ok(())
+    }
+
+    /// Drops `lobby_id_hash` if listed. Returns whether it was.
+    pub fn remove(&mut self, lobby_id_hash: &[u8; 32]) -> bool {
+        match self.lobbies.iter().position(|lobby| lobby == lobby_id_hash) {
+            Some(i) => {
+                self.lobbies.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An all-zero escrow: no wagers, no fee, every option off.
+    fn escrow() -> MatchEscrow {
+        let data = [0u8; MatchEscrow::INIT_SPACE];
+        MatchEscrow::deserialize(&mut &data[..]).unwrap()
+    }
+
+    fn funded(host: u64, opponent: u64, fee_bps: u16) -> MatchEscrow {
+        let mut escrow = escrow();
+        escrow.host_deposited_amount = host;
+        escrow.opponent_deposited_amount = opponent;
+        escrow.fee_bps = fee_bps;
+        escrow
+    }
+
+    #[test]
+    fn layout_fills_init_space_exactly() {
+        // `migrate_escrow` grows accounts to `8 + INIT_SPACE`, so the two
+        // must agree or migrated escrows would fail to deserialize
+        let mut data = Vec::new();
+        escrow().serialize(&mut data).unwrap();
+        assert_eq!(data.len(), MatchEscrow::INIT_SPACE);
+    }
+
+    #[test]
+    fn distribution_handles_a_u64_max_pot() {
+        let (total, fee, payout) = funded(u64::MAX, 0, 1_000).compute_distribution().unwrap();
+        assert_eq!(total, u64::MAX);
+        assert_eq!(fee, u64::MAX / 10);
+        assert_eq!(fee + payout, u64::MAX);
+
+        let (_, fee, payout) = funded(u64::MAX / 2, u64::MAX / 2 + 1, 10_000)
+            .compute_distribution()
+            .unwrap();
+        assert_eq!((fee, payout), (u64::MAX, 0));
+    }
+
+    #[test]
+    fn distribution_rejects_pots_past_u64_max() {
+        assert!(funded(u64::MAX, 1, 1_000).compute_distribution().is_err());
+
+        let mut escrow = funded(u64::MAX, 0, 1_000);
+        escrow.bonus_pool = 1;
+        assert!(escrow.compute_distribution().is_err());
+    }
+
+    #[test]
+    fn split_fee_handles_a_u64_max_pot() {
+        let mut escrow = funded(u64::MAX, 0, 1_000);
+        escrow.referrer = Pubkey::new_unique();
+        escrow.referral_bps = 500;
+        escrow.burn_bps = 200;
+        let (total, fee, _) = escrow.compute_distribution().unwrap();
+        let (referral, burn, treasury_fee) = escrow.split_fee(total, fee).unwrap();
+        assert_eq!(referral, u64::MAX / 20);
+        assert_eq!(burn, u64::MAX / 50);
+        assert_eq!(referral + burn + treasury_fee, fee);
+    }
+
+    #[test]
+    fn player_predicates() {
+        let mut escrow = escrow();
+        let (host, opponent, outsider) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        escrow.host = host;
+        escrow.opponent = opponent;
+
+        assert!(escrow.is_player(&host));
+        assert!(escrow.is_player(&opponent));
+        assert!(!escrow.is_player(&outsider));
+
+        assert_eq!(escrow.other_player(&host), Some(opponent));
+        assert_eq!(escrow.other_player(&opponent), Some(host));
+        assert_eq!(escrow.other_player(&outsider), None);
+    }
+
+    #[test]
+    fn both_funded_needs_both_seats() {
+        let mut escrow = escrow();
+        assert!(!escrow.both_funded());
+        escrow.host_deposited = true;
+        assert!(!escrow.both_funded());
+        escrow.opponent_deposited = true;
+        assert!(escrow.both_funded());
+        escrow.host_deposited = false;
+        assert!(!escrow.both_funded());
+    }
+
+    #[test]
+    fn rounding_modes_on_odd_pots() {
+        // (pot, floor, ceil, half-even) at a 10% fee
+        let cases = [
+            (20, 2, 2, 2),
+            (21, 2, 3, 2),
+            (15, 1, 2, 2),
+            (25, 2, 3, 2),
+            (27, 2, 3, 3),
+            (1, 0, 1, 0),
+            (5, 0, 1, 0),
+            (6, 0, 1, 1),
+        ];
+        for (pot, floor, ceil, half_even) in cases {
+            for (mode, expected) in [
+                (RoundingMode::Floor, floor),
+                (RoundingMode::Ceil, ceil),
+                (RoundingMode::HalfEven, half_even),
+            ] {
+                let mut escrow = funded(pot, 0, 1_000);
+                escrow.rounding = mode as u8;
+                let (total, fee, payout) = escrow.compute_distribution().unwrap();
+                assert_eq!(fee, expected, "pot {pot}, mode {}", mode as u8);
+                assert_eq!(fee + payout, total);
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_rounding_falls_back_to_floor() {
+        let mut escrow = funded(15, 0, 1_000);
+        escrow.rounding = 7;
+        assert_eq!(escrow.compute_distribution().unwrap().1, 1);
+    }
+
+    #[test]
+    fn status_transitions() {
+        use EscrowStatus::*;
+        let all = [Created, PartiallyFunded, Funded, Settled, Refunded, Disputed];
+        let allowed = [
+            (Created, PartiallyFunded),
+            (Created, Funded),
+            (Created, Refunded),
+            (PartiallyFunded, Funded),
+            (PartiallyFunded, Refunded),
+            (Funded, Settled),
+            (Funded, Refunded),
+            (Funded, Disputed),
+            (Disputed, Funded),
+            (Disputed, Refunded),
+        ];
+        for from in all {
+            for to in all {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&(from, to)),
+                    "{} -> {}",
+                    from as u8,
+                    to as u8
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn transition_rejects_disallowed_edges() {
+        let mut escrow = escrow();
+        assert!(escrow.transition(EscrowStatus::Settled).is_err());
+        assert_eq!(escrow.status, EscrowStatus::Created as u8);
+        escrow.transition(EscrowStatus::Funded).unwrap();
+        escrow.transition(EscrowStatus::Settled).unwrap();
+        assert!(escrow.transition(EscrowStatus::Refunded).is_err());
+        assert_eq!(escrow.status, EscrowStatus::Settled as u8);
+    }
+
+    #[test]
+    fn fee_clamps_to_min_and_max() {
+        let with_bounds = |pot: u64, min_fee: u64, max_fee: u64| {
+            let mut escrow = funded(pot, 0, 1_000);
+            escrow.min_fee = min_fee;
+            escrow.max_fee = max_fee;
+            let (total, fee, payout) = escrow.compute_distribution().unwrap();
+            assert_eq!(fee + payout, total);
+            fee
+        };
+        // max_fee == 0 is uncapped; a cap above the fee doesn't bind
+        assert_eq!(with_bounds(10_000, 0, 0), 1_000);
+        assert_eq!(with_bounds(10_000, 0, 5_000), 1_000);
+        assert_eq!(with_bounds(10_000, 0, 300), 300);
+        // The floor raises a small fee, but never past half the pot
+        assert_eq!(with_bounds(100, 25, 0), 25);
+        assert_eq!(with_bounds(100, 500, 0), 50);
+        assert_eq!(with_bounds(0, 500, 0), 0);
+        // The cap applies after the floor
+        assert_eq!(with_bounds(100, 500, 30), 30);
+    }
+
+    #[test]
+    fn capped_fee_pays_carve_outs_first() {
+        let mut escrow = funded(10_000, 0, 1_000);
+        escrow.referrer = Pubkey::new_unique();
+        escrow.referral_bps = 500;
+        escrow.burn_bps = 200;
+        escrow.max_fee = 600;
+        let (total, fee, _) = escrow.compute_distribution().unwrap();
+        assert_eq!(fee, 600);
+        assert_eq!(escrow.split_fee(total, fee).unwrap(), (500, 100, 0));
+    }
+
+    #[test]
+    fn pda_matches_the_declared_seeds() {
+        let lobby_id_hash = lobby_seed(b"lobby-1", 3);
+        let expected =
+            Pubkey::find_program_address(&[b"escrow", lobby_id_hash.as_ref()], &crate::ID);
+        assert_eq!(MatchEscrow::pda(&lobby_id_hash, &crate::ID), expected);
+        assert_ne!(
+            MatchEscrow::pda(&lobby_seed(b"lobby-1", 4), &crate::ID).0,
+            expected.0
+        );
+    }
+
+    #[test]
+    fn deposit_method_cannot_be_mixed() {
+        let mut escrow = escrow();
+        escrow.set_deposit_method(true, DepositMethod::OnChain).unwrap();
+        // Repeating the same method (e.g. a second installment) is fine
+        escrow.set_deposit_method(true, DepositMethod::OnChain).unwrap();
+        assert!(escrow.set_deposit_method(true, DepositMethod::Confirmed).is_err());
+        assert_eq!(escrow.host_deposit_method, DepositMethod::OnChain as u8);
+
+        // Each seat is tracked on its own
+        escrow.set_deposit_method(false, DepositMethod::Confirmed).unwrap();
+        assert!(escrow.set_deposit_method(false, DepositMethod::OnChain).is_err());
+        assert_eq!(escrow.opponent_deposit_method, DepositMethod::Confirmed as u8);
+    }
+
+    #[test]
+    fn expiry_starts_one_second_after_expires_at() {
+        let mut escrow = escrow();
+        escrow.expires_at = 1_000;
+        let at = |unix_timestamp| Clock {
+            unix_timestamp,
+            ..Clock::default()
+        };
+        assert!(!escrow.expired(&at(999)));
+        assert!(!escrow.expired(&at(1_000)));
+        assert!(escrow.expired(&at(1_001)));
+    }
+
+    #[test]
+    fn a_second_payout_cannot_begin_while_one_is_in_progress() {
+        let mut escrow = escrow();
+        escrow.begin_settling().unwrap();
+        assert!(escrow.settling);
+        assert_eq!(
+            escrow.begin_settling().unwrap_err(),
+            EscrowError::SettlementInProgress.into()
+        );
+
+        // Completing the payout clears the guard
+        escrow.settling = false;
+        assert!(escrow.begin_settling().is_ok());
+    }
+
+    #[test]
+    fn min_duration_and_challenge_gate_a_draw() {
+        let mut escrow = escrow();
+        escrow.funded_at = 1_000;
+        escrow.min_match_duration = 60;
+        let at = |unix_timestamp| Clock {
+            unix_timestamp,
+            ..Clock::default()
+        };
+        let draw = Pubkey::default();
+        assert_eq!(
+            escrow.check_min_duration(&at(1_059)).unwrap_err(),
+            EscrowError::MatchTooShort.into()
+        );
+        assert!(escrow.check_min_duration(&at(1_060)).is_ok());
+
+        // A proposed winner can't be overridden by a draw, even after the window
+        escrow.proposed_winner = Pubkey::new_unique();
+        escrow.challenge_deadline = 1_100;
+        assert_eq!(
+            escrow.check_challenge(&draw, &at(1_200)).unwrap_err(),
+            EscrowError::InvalidWinner.into()
+        );
+        escrow.proposed_winner = Pubkey::default();
+        assert!(escrow.check_challenge(&draw, &at(1_060)).is_ok());
+    }
 }
@@ -1,15 +1,9 @@
 /**
  * Comprehensive Devnet Smoke Test for match-escrow program
  *
- * Tests every instruction and error path:
- *   Test 1: Full lifecycle — init → host deposit → opponent deposit → settle (host wins)
- *   Test 2: Forfeit path — init → deposits → forfeit (host forfeits, opponent wins)
- *   Test 3: Opponent wins — init → deposits → settle (opponent wins)
- *   Test 4: Confirm deposit (x402) — init → host deposits SOL → authority confirms opponent → settle
- *   Test 5: Error - double deposit rejected
- *   Test 6: Error - unauthorized settle rejected
- *   Test 7: Error - settle before both deposits rejected
- *   Test 8: Error - invalid winner rejected
+ * Runs numbered tests against the deployed program, covering the main
+ * instructions (native SOL and SPL) and their error paths. Each test
+ * prints its own banner, so the output doubles as the list of cases.
  */
 import {
   Connection,
@@ -30,7 +24,16 @@ import * as crypto from "crypto";
 const PROGRAM_ID = new PublicKey("3483xDBJewW1qERNjMrQuvgoFj2utKgZGFWrKBgCiHKS");
 const RPC_URL = "https://api.devnet.solana.com";
 const ESCROW_SEED = Buffer.from("escrow");
+const CONFIG_PDA = PublicKey.findProgramAddressSync([Buffer.from("config")], PROGRAM_ID)[0];
 const WAGER_LAMPORTS = 1_000_000; // 0.001 SOL per player
+const WAGER_TOKENS = 1_000_000; // 1 token at 6 decimals per player
+const TOKEN_PROGRAM_ID = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const DEPOSIT_WINDOW_SECS = 10 * 60;
+const MATCH_WINDOW_SECS = 60 * 60;
+// ForfeitReason codes
+const FORFEIT_DISCONNECT = 0;
+const FORFEIT_SURRENDER = 2;
 
 // ──────────────────────────────────────────────
 // Helpers
@@ -54,6 +57,20 @@ function u64Buf(val: number | bigint): Buffer {
   return buf;
 }
 
+function i64Buf(val: number | bigint): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigInt64LE(BigInt(val));
+  return buf;
+}
+
+function nowSecs(): number {
+  return Math.floor(Date.now() / 1000);
+}
+
+function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
 function deriveEscrowPda(lobbyIdHash: Buffer): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([ESCROW_SEED, lobbyIdHash], PROGRAM_ID);
 }
@@ -62,97 +79,490 @@ function hashLobby(id: string): Buffer {
   return crypto.createHash("sha256").update(id).digest();
 }
 
+function ata(owner: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  )[0];
+}
+
 // Sentinel for Anchor optional accounts = program ID
 const NONE = PROGRAM_ID;
 
 // ──────────────────────────────────────────────
 // Instruction builders
 // ──────────────────────────────────────────────
+interface InitParams {
+  lobbyIdHash: Buffer;
+  host: PublicKey;
+  opponent: PublicKey;
+  tokenMint: PublicKey;
+  treasury: PublicKey;
+  hostWager?: number;
+  opponentWager?: number;
+  feeBps?: number;
+  depositDeadline?: number;
+  matchDeadline?: number;
+  settlementDeadline?: number;
+  freeEntry?: boolean;
+  retainRecord?: boolean;
+  settleAuthority?: PublicKey;
+  allowPlayerTreasury?: boolean;
+  minMatchDuration?: number;
+  sponsored?: boolean;
+}
+
+function u8Buf(val: number): Buffer {
+  return Buffer.from([val]);
+}
+
+function u16Buf(val: number): Buffer {
+  const buf = Buffer.alloc(2);
+  buf.writeUInt16LE(val);
+  return buf;
+}
+
+function boolBuf(val: boolean): Buffer {
+  return Buffer.from([val ? 1 : 0]);
+}
+
+// Borsh Option<T>: 0 for None, 1 followed by the value for Some
+function optionBuf(val?: Buffer): Buffer {
+  return val ? Buffer.concat([Buffer.from([1]), val]) : Buffer.from([0]);
+}
+
+// Borsh layout of InitializeEscrowParams, field by field
+function encodeInitParams(p: InitParams): Buffer {
+  const depositDeadline = p.depositDeadline ?? nowSecs() + DEPOSIT_WINDOW_SECS;
+  const matchDeadline = p.matchDeadline ?? depositDeadline + MATCH_WINDOW_SECS;
+  return Buffer.concat([
+    p.lobbyIdHash,
+    pubkeyBuf(p.host),
+    pubkeyBuf(p.opponent),
+    u64Buf(p.hostWager ?? WAGER_LAMPORTS),
+    u64Buf(p.opponentWager ?? WAGER_LAMPORTS),
+    pubkeyBuf(p.tokenMint),
+    pubkeyBuf(p.treasury),
+    i64Buf(depositDeadline),
+    optionBuf(p.feeBps === undefined ? undefined : u16Buf(p.feeBps)),
+    i64Buf(matchDeadline),
+    u8Buf(1), // rounds_to_win
+    boolBuf(p.freeEntry ?? false),
+    optionBuf(), // min_wager
+    optionBuf(), // max_wager
+    boolBuf(p.retainRecord ?? false),
+    optionBuf(), // deposit_deadline_slot
+    optionBuf(), // match_deadline_slot
+    boolBuf(false), // use_global_treasury
+    boolBuf(false), // require_authorized_deposit
+    optionBuf(), // match_ref
+    i64Buf(p.settlementDeadline ?? matchDeadline + MATCH_WINDOW_SECS),
+    optionBuf(), // oracle
+    optionBuf(), // referrer
+    u16Buf(0), // referral_bps
+    u8Buf(0), // rounding: floor
+    optionBuf(), // required_collection
+    optionBuf(p.settleAuthority && pubkeyBuf(p.settleAuthority)),
+    boolBuf(p.allowPlayerTreasury ?? false),
+    u16Buf(0), // burn_bps
+    u64Buf(0), // min_fee
+    u64Buf(0), // max_fee
+    i64Buf(p.minMatchDuration ?? 0),
+    optionBuf(), // default_winner
+    u16Buf(0), // crank_reward_bps
+    boolBuf(false), // require_join_approval
+    boolBuf(p.sponsored ?? false),
+  ]);
+}
+
 function ixInitialize(
-  authority: PublicKey, escrowPda: PublicKey, lobbyIdHash: Buffer,
-  host: PublicKey, opponent: PublicKey, wager: number,
-  tokenMint: PublicKey, treasury: PublicKey
+  authority: PublicKey, escrowPda: PublicKey, params: InitParams,
+  payer: PublicKey = authority
 ): TransactionInstruction {
   return new TransactionInstruction({
     keys: [
-      { pubkey: authority, isSigner: true, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: escrowPda, isSigner: false, isWritable: true },
+      { pubkey: CONFIG_PDA, isSigner: false, isWritable: false },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: PROGRAM_ID,
-    data: Buffer.concat([
-      disc("initialize_escrow"), lobbyIdHash,
-      pubkeyBuf(host), pubkeyBuf(opponent), u64Buf(wager),
-      pubkeyBuf(tokenMint), pubkeyBuf(treasury),
-    ]),
+    data: Buffer.concat([disc("initialize_escrow"), encodeInitParams(params)]),
   });
 }
 
-function ixDeposit(depositor: PublicKey, escrowPda: PublicKey): TransactionInstruction {
+// initialize_escrow_spl: same params, and it also creates the escrow's ATA
+function ixInitializeSpl(
+  authority: PublicKey, escrowPda: PublicKey, params: InitParams,
+  payer: PublicKey = authority
+): TransactionInstruction {
   return new TransactionInstruction({
     keys: [
-      { pubkey: depositor, isSigner: true, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: escrowPda, isSigner: false, isWritable: true },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
+      { pubkey: CONFIG_PDA, isSigner: false, isWritable: false },
+      { pubkey: params.tokenMint, isSigner: false, isWritable: false },
+      { pubkey: ata(escrowPda, params.tokenMint), isSigner: false, isWritable: true },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: PROGRAM_ID,
+    data: Buffer.concat([disc("initialize_escrow_spl"), encodeInitParams(params)]),
+  });
+}
+
+// Token accounts an SPL deposit moves the stake between
+interface SplDeposit {
+  source: PublicKey;
+  escrowTa: PublicKey;
+  mint: PublicKey;
+}
+
+function depositKeys(depositor: PublicKey, escrowPda: PublicKey, spl?: SplDeposit) {
+  const none = { pubkey: NONE, isSigner: false, isWritable: false };
+  return [
+    { pubkey: depositor, isSigner: true, isWritable: true },
+    { pubkey: escrowPda, isSigner: false, isWritable: true },
+    { pubkey: CONFIG_PDA, isSigner: false, isWritable: false },
+    none, // authority
+    none, // delegate
+    ...(spl ? [
+      { pubkey: spl.source, isSigner: false, isWritable: true },
+      { pubkey: spl.escrowTa, isSigner: false, isWritable: true },
+      { pubkey: spl.mint, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ] : Array(4).fill(none)),
+    none, // entry_nft_token_account
+    none, // entry_nft_metadata
+    none, // player_index
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+}
+
+function ixDeposit(
+  depositor: PublicKey, escrowPda: PublicKey, spl?: SplDeposit
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: depositKeys(depositor, escrowPda, spl),
+    programId: PROGRAM_ID,
     data: disc("deposit"),
   });
 }
 
-function ixConfirmDeposit(authority: PublicKey, escrowPda: PublicKey, depositor: PublicKey): TransactionInstruction {
+// join takes the deposit accounts and claims the open opponent seat
+function ixJoin(joiner: PublicKey, escrowPda: PublicKey): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: depositKeys(joiner, escrowPda),
+    programId: PROGRAM_ID,
+    data: disc("join"),
+  });
+}
+
+function ixDepositBoth(
+  host: PublicKey, opponent: PublicKey, escrowPda: PublicKey
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: host, isSigner: true, isWritable: true },
+      { pubkey: opponent, isSigner: true, isWritable: true },
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+      { pubkey: CONFIG_PDA, isSigner: false, isWritable: false },
+      // authority, token accounts, mint and token_program: native SOL
+      ...Array(6).fill({ pubkey: NONE, isSigner: false, isWritable: false }),
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId: PROGRAM_ID,
+    data: disc("deposit_both"),
+  });
+}
+
+function ixDepositInstallment(
+  depositor: PublicKey, escrowPda: PublicKey, amount: number, spl?: SplDeposit
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: depositKeys(depositor, escrowPda, spl),
+    programId: PROGRAM_ID,
+    data: Buffer.concat([disc("deposit_installment"), u64Buf(amount)]),
+  });
+}
+
+// paymentRef stands in for the x402 settlement hash; confirmNonce must
+// exceed the escrow's last confirmed nonce
+function ixConfirmDeposit(
+  authority: PublicKey, escrowPda: PublicKey, depositor: PublicKey,
+  paymentRef: Buffer = crypto.randomBytes(32), confirmNonce: number = 1
+): TransactionInstruction {
   return new TransactionInstruction({
     keys: [
       { pubkey: authority, isSigner: true, isWritable: false },
       { pubkey: escrowPda, isSigner: false, isWritable: true },
     ],
     programId: PROGRAM_ID,
-    data: Buffer.concat([disc("confirm_deposit"), pubkeyBuf(depositor)]),
+    data: Buffer.concat([
+      disc("confirm_deposit"), pubkeyBuf(depositor), paymentRef, u64Buf(confirmNonce),
+    ]),
   });
 }
 
+// Token accounts an SPL payout moves the pot between
+interface SplSettle {
+  winnerTa: PublicKey;
+  treasuryTa: PublicKey;
+  escrowTa: PublicKey;
+  mint: PublicKey;
+}
+
+// Settle accounts shared by settle and forfeit. The config, referral,
+// result log, fee vault, player index and payout_recipient accounts are
+// always absent here, and the token accounts too for a native SOL payout.
+function settleKeys(
+  authority: PublicKey, escrowPda: PublicKey, rentPayer: PublicKey,
+  winner: PublicKey, treasury: PublicKey, spl?: SplSettle
+) {
+  const none = { pubkey: NONE, isSigner: false, isWritable: false };
+  return [
+    { pubkey: authority, isSigner: true, isWritable: true },
+    { pubkey: escrowPda, isSigner: false, isWritable: true },
+    { pubkey: rentPayer, isSigner: false, isWritable: true },
+    { pubkey: winner, isSigner: false, isWritable: true },
+    { pubkey: treasury, isSigner: false, isWritable: true },
+    ...(spl ? [
+      { pubkey: spl.winnerTa, isSigner: false, isWritable: true },
+      { pubkey: spl.treasuryTa, isSigner: false, isWritable: true },
+      { pubkey: spl.escrowTa, isSigner: false, isWritable: true },
+      { pubkey: spl.mint, isSigner: false, isWritable: true },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ] : Array(5).fill(none)),
+    ...Array(8).fill(none),
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+}
+
 function ixSettle(
   authority: PublicKey, escrowPda: PublicKey,
-  winner: PublicKey, treasury: PublicKey, winnerArg: PublicKey
+  winner: PublicKey, treasury: PublicKey, winnerArg: PublicKey,
+  rentToWinner = false, rentPayer: PublicKey = authority, spl?: SplSettle
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: settleKeys(authority, escrowPda, rentPayer, winner, treasury, spl),
+    programId: PROGRAM_ID,
+    data: Buffer.concat([disc("settle"), pubkeyBuf(winnerArg), boolBuf(rentToWinner)]),
+  });
+}
+
+function ixForfeit(
+  authority: PublicKey, escrowPda: PublicKey,
+  winner: PublicKey, treasury: PublicKey, forfeiterArg: PublicKey,
+  reason: number = FORFEIT_DISCONNECT, rentToWinner = false,
+  rentPayer: PublicKey = authority
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: settleKeys(authority, escrowPda, rentPayer, winner, treasury),
+    programId: PROGRAM_ID,
+    data: Buffer.concat([
+      disc("forfeit"), pubkeyBuf(forfeiterArg), u8Buf(reason), boolBuf(rentToWinner),
+    ]),
+  });
+}
+
+function ixSettleTiebreak(
+  authority: PublicKey, escrowPda: PublicKey, winner: PublicKey, treasury: PublicKey
 ): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: settleKeys(authority, escrowPda, authority, winner, treasury),
+    programId: PROGRAM_ID,
+    data: disc("settle_tiebreak"),
+  });
+}
+
+function ixRaiseDispute(caller: PublicKey, escrowPda: PublicKey): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: caller, isSigner: true, isWritable: false },
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+    ],
+    programId: PROGRAM_ID,
+    data: disc("raise_dispute"),
+  });
+}
+
+// Native sponsor_deposit: no token accounts, mint or token program
+function ixSponsorDeposit(authority: PublicKey, escrowPda: PublicKey): TransactionInstruction {
   return new TransactionInstruction({
     keys: [
       { pubkey: authority, isSigner: true, isWritable: true },
       { pubkey: escrowPda, isSigner: false, isWritable: true },
-      { pubkey: winner, isSigner: false, isWritable: true },
-      { pubkey: treasury, isSigner: false, isWritable: true },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
+      { pubkey: CONFIG_PDA, isSigner: false, isWritable: false },
+      ...Array(4).fill({ pubkey: NONE, isSigner: false, isWritable: false }),
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: PROGRAM_ID,
-    data: Buffer.concat([disc("settle"), pubkeyBuf(winnerArg)]),
+    data: disc("sponsor_deposit"),
   });
 }
 
-function ixForfeit(
-  authority: PublicKey, escrowPda: PublicKey,
-  winner: PublicKey, treasury: PublicKey, forfeiterArg: PublicKey
+function ixMutualCancel(
+  escrowPda: PublicKey, host: PublicKey, opponent: PublicKey, rentPayer: PublicKey
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+      { pubkey: host, isSigner: true, isWritable: true },
+      { pubkey: opponent, isSigner: true, isWritable: true },
+      { pubkey: rentPayer, isSigner: false, isWritable: true },
+      // Token accounts, mint and token_program: native SOL refund
+      ...Array(5).fill({ pubkey: NONE, isSigner: false, isWritable: false }),
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId: PROGRAM_ID,
+    data: disc("mutual_cancel"),
+  });
+}
+
+function ixSettleDoubleForfeit(
+  authority: PublicKey, escrowPda: PublicKey, rentPayer: PublicKey,
+  host: PublicKey, opponent: PublicKey, treasury: PublicKey, abandonmentFeeBps: number,
+  spl?: {
+    hostTa: PublicKey; opponentTa: PublicKey; treasuryTa: PublicKey;
+    escrowTa: PublicKey; mint: PublicKey;
+  }
 ): TransactionInstruction {
   return new TransactionInstruction({
     keys: [
       { pubkey: authority, isSigner: true, isWritable: true },
       { pubkey: escrowPda, isSigner: false, isWritable: true },
-      { pubkey: winner, isSigner: false, isWritable: true },
+      { pubkey: rentPayer, isSigner: false, isWritable: true },
+      { pubkey: host, isSigner: false, isWritable: true },
+      { pubkey: opponent, isSigner: false, isWritable: true },
       { pubkey: treasury, isSigner: false, isWritable: true },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
-      { pubkey: NONE, isSigner: false, isWritable: false },
+      ...(spl ? [
+        { pubkey: spl.hostTa, isSigner: false, isWritable: true },
+        { pubkey: spl.opponentTa, isSigner: false, isWritable: true },
+        { pubkey: spl.treasuryTa, isSigner: false, isWritable: true },
+        { pubkey: spl.escrowTa, isSigner: false, isWritable: true },
+        { pubkey: spl.mint, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ] : Array(6).fill({ pubkey: NONE, isSigner: false, isWritable: false })),
+      { pubkey: NONE, isSigner: false, isWritable: false }, // config
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId: PROGRAM_ID,
+    data: Buffer.concat([disc("settle_double_forfeit"), u16Buf(abandonmentFeeBps)]),
+  });
+}
+
+function ixReclaimUnfunded(
+  caller: PublicKey, escrowPda: PublicKey, authority: PublicKey, depositor: PublicKey,
+  spl?: { depositorTa: PublicKey; escrowTa: PublicKey; mint: PublicKey }
+): TransactionInstruction {
+  const none = { pubkey: NONE, isSigner: false, isWritable: false };
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: caller, isSigner: true, isWritable: false },
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: false, isWritable: true },
+      { pubkey: depositor, isSigner: false, isWritable: true },
+      ...(spl ? [
+        { pubkey: spl.depositorTa, isSigner: false, isWritable: true },
+        { pubkey: spl.escrowTa, isSigner: false, isWritable: true },
+        { pubkey: spl.mint, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ] : Array(4).fill(none)),
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId: PROGRAM_ID,
+    data: disc("reclaim_unfunded"),
+  });
+}
+
+function ixExpireRefund(
+  caller: PublicKey, escrowPda: PublicKey, host: PublicKey, opponent: PublicKey,
+  rentPayer: PublicKey
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: caller, isSigner: true, isWritable: false },
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+      { pubkey: host, isSigner: false, isWritable: true },
+      { pubkey: opponent, isSigner: false, isWritable: true },
+      { pubkey: rentPayer, isSigner: false, isWritable: true },
+      // Token accounts, mint and token_program: native SOL refund
+      ...Array(5).fill({ pubkey: NONE, isSigner: false, isWritable: false }),
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId: PROGRAM_ID,
-    data: Buffer.concat([disc("forfeit"), pubkeyBuf(forfeiterArg)]),
+    data: disc("expire_refund"),
+  });
+}
+
+function ixCancelEscrow(authority: PublicKey, escrowPda: PublicKey): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: authority, isSigner: true, isWritable: true },
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+    ],
+    programId: PROGRAM_ID,
+    data: disc("cancel_escrow"),
+  });
+}
+
+function ixUpdateWager(
+  authority: PublicKey, escrowPda: PublicKey, hostWager: number, opponentWager: number
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+    ],
+    programId: PROGRAM_ID,
+    data: Buffer.concat([disc("update_wager"), u64Buf(hostWager), u64Buf(opponentWager)]),
+  });
+}
+
+function ixTransferAuthority(
+  authority: PublicKey, escrowPda: PublicKey, newAuthority: PublicKey
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: escrowPda, isSigner: false, isWritable: true },
+    ],
+    programId: PROGRAM_ID,
+    data: Buffer.concat([disc("transfer_authority"), pubkeyBuf(newAuthority)]),
+  });
+}
+
+// SPL Token instructions, built by hand like the program's own
+
+function ixCreateAta(payer: PublicKey, owner: PublicKey, mint: PublicKey): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: ata(owner, mint), isSigner: false, isWritable: true },
+      { pubkey: owner, isSigner: false, isWritable: false },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    programId: ASSOCIATED_TOKEN_PROGRAM_ID,
+    data: Buffer.from([1]), // CreateIdempotent
+  });
+}
+
+function ixMintTo(
+  mint: PublicKey, dest: PublicKey, authority: PublicKey, amount: number
+): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: mint, isSigner: false, isWritable: true },
+      { pubkey: dest, isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+    ],
+    programId: TOKEN_PROGRAM_ID,
+    data: Buffer.concat([u8Buf(7), u64Buf(amount)]), // MintTo
   });
 }
 
@@ -200,16 +610,63 @@ async function expectFailure(
   }
 }
 
+// A settle that loses a race against another settle/forfeit hits a closed
+// escrow and fails with AccountNotInitialized, same as a bogus PDA. An
+// escrow that existed has transaction history; one that never did has none.
+async function classifySettleFailure(
+  connection: Connection, escrowPda: PublicKey, err: any
+): Promise<"already-settled" | "never-existed" | "other"> {
+  const logs = err.transactionLogs?.join(" ") || err.message || "";
+  if (logs.includes("AlreadySettled")) return "already-settled";
+  if (!logs.includes("AccountNotInitialized")) return "other";
+  const history = await connection.getSignaturesForAddress(escrowPda, { limit: 1 });
+  return history.length > 0 ? "already-settled" : "never-existed";
+}
+
+async function fund(
+  connection: Connection, from: Keypair, to: PublicKey, lamports: number
+) {
+  await sendAndConfirmTransaction(connection,
+    new Transaction().add(SystemProgram.transfer({
+      fromPubkey: from.publicKey, toPubkey: to, lamports,
+    })), [from]);
+}
+
+// A 6-decimal test mint, standing in for USDC, minted by `authority`
+async function createMint(connection: Connection, authority: Keypair): Promise<PublicKey> {
+  const mint = Keypair.generate();
+  const initMint = new TransactionInstruction({
+    keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+    programId: TOKEN_PROGRAM_ID,
+    // InitializeMint2: decimals, mint authority, no freeze authority
+    data: Buffer.concat([u8Buf(20), u8Buf(6), pubkeyBuf(authority.publicKey), u8Buf(0)]),
+  });
+  await sendAndConfirmTransaction(connection,
+    new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: authority.publicKey, newAccountPubkey: mint.publicKey,
+        lamports: await connection.getMinimumBalanceForRentExemption(82),
+        space: 82, programId: TOKEN_PROGRAM_ID,
+      }),
+      initMint
+    ), [authority, mint]);
+  return mint.publicKey;
+}
+
+async function tokenBalance(connection: Connection, account: PublicKey): Promise<number> {
+  return Number((await connection.getTokenAccountBalance(account)).value.amount);
+}
+
 // ──────────────────────────────────────────────
 // Setup helper: create funded escrow with both deposits
 // ──────────────────────────────────────────────
 async function setupFullEscrow(
-  connection: Connection, authority: Keypair, testName: string
+  connection: Connection, authority: Keypair, treasury: PublicKey, testName: string,
+  overrides: Partial<InitParams> = {}
 ) {
   const opponent = Keypair.generate();
   const lobbyIdHash = hashLobby(`${testName}-${Date.now()}-${Math.random()}`);
   const [escrowPda] = deriveEscrowPda(lobbyIdHash);
-  const treasury = authority.publicKey;
 
   // Fund opponent
   await sendAndConfirmTransaction(connection,
@@ -220,11 +677,10 @@ async function setupFullEscrow(
 
   // Initialize
   await sendAndConfirmTransaction(connection,
-    new Transaction().add(ixInitialize(
-      authority.publicKey, escrowPda, lobbyIdHash,
-      authority.publicKey, opponent.publicKey, WAGER_LAMPORTS,
-      PublicKey.default, treasury
-    )), [authority]);
+    new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+      lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+      tokenMint: PublicKey.default, treasury, ...overrides,
+    })), [authority]);
 
   // Both deposit
   await sendAndConfirmTransaction(connection,
@@ -232,7 +688,53 @@ async function setupFullEscrow(
   await sendAndConfirmTransaction(connection,
     new Transaction().add(ixDeposit(opponent.publicKey, escrowPda)), [opponent]);
 
-  return { opponent, lobbyIdHash, escrowPda, treasury };
+  return { opponent, lobbyIdHash, escrowPda };
+}
+
+// SPL counterpart of setupFullEscrow: token accounts for both players and
+// the treasury, an escrow in `mint`, and the host's deposit. The opponent
+// deposits too unless `opponentDeposits` is false.
+async function setupSplEscrow(
+  connection: Connection, authority: Keypair, treasury: PublicKey, mint: PublicKey,
+  testName: string, opponentDeposits = true, overrides: Partial<InitParams> = {}
+) {
+  const opponent = Keypair.generate();
+  const lobbyIdHash = hashLobby(`${testName}-${Date.now()}-${Math.random()}`);
+  const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+  const hostTa = ata(authority.publicKey, mint);
+  const opponentTa = ata(opponent.publicKey, mint);
+  const treasuryTa = ata(treasury, mint);
+  const escrowTa = ata(escrowPda, mint);
+
+  await fund(connection, authority, opponent.publicKey, 10_000_000);
+  await sendAndConfirmTransaction(connection,
+    new Transaction().add(
+      ixCreateAta(authority.publicKey, authority.publicKey, mint),
+      ixCreateAta(authority.publicKey, opponent.publicKey, mint),
+      ixCreateAta(authority.publicKey, treasury, mint),
+      ixMintTo(mint, hostTa, authority.publicKey, WAGER_TOKENS),
+      ixMintTo(mint, opponentTa, authority.publicKey, WAGER_TOKENS)
+    ), [authority]);
+
+  await sendAndConfirmTransaction(connection,
+    new Transaction().add(ixInitializeSpl(authority.publicKey, escrowPda, {
+      lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+      tokenMint: mint, treasury, hostWager: WAGER_TOKENS, opponentWager: WAGER_TOKENS,
+      ...overrides,
+    })), [authority]);
+
+  await sendAndConfirmTransaction(connection,
+    new Transaction().add(ixDeposit(authority.publicKey, escrowPda, {
+      source: hostTa, escrowTa, mint,
+    })), [authority]);
+  if (opponentDeposits) {
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixDeposit(opponent.publicKey, escrowPda, {
+        source: opponentTa, escrowTa, mint,
+      })), [opponent]);
+  }
+
+  return { opponent, lobbyIdHash, escrowPda, hostTa, opponentTa, treasuryTa, escrowTa };
 }
 
 // ──────────────────────────────────────────────
@@ -252,12 +754,22 @@ async function main() {
     await connection.confirmTransaction(sig, "confirmed");
   }
 
+  // Fee destination for every test escrow. Init rejects a player as the
+  // treasury, and a fresh account needs its rent-exempt minimum before it
+  // can take a fee smaller than that.
+  const treasury = Keypair.generate().publicKey;
+  await sendAndConfirmTransaction(connection,
+    new Transaction().add(SystemProgram.transfer({
+      fromPubkey: authority.publicKey, toPubkey: treasury,
+      lamports: await connection.getMinimumBalanceForRentExemption(0),
+    })), [authority]);
+
   // ═══════════════════════════════════════════
   // Test 1: Full lifecycle — settle (host wins)
   // ═══════════════════════════════════════════
   console.log("═══ Test 1: Settle (host wins) ═══");
   {
-    const { escrowPda, treasury } = await setupFullEscrow(connection, authority, "t1");
+    const { escrowPda } = await setupFullEscrow(connection, authority, treasury, "t1");
 
     const tx = new Transaction().add(ixSettle(
       authority.publicKey, escrowPda,
@@ -280,7 +792,7 @@ async function main() {
   // ═══════════════════════════════════════════
   console.log("\n═══ Test 2: Settle (opponent wins) ═══");
   {
-    const { opponent, escrowPda, treasury } = await setupFullEscrow(connection, authority, "t2");
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t2");
 
     const oppBalBefore = await connection.getBalance(opponent.publicKey);
     const tx = new Transaction().add(ixSettle(
@@ -306,7 +818,7 @@ async function main() {
   // ═══════════════════════════════════════════
   console.log("\n═══ Test 3: Forfeit (host forfeits) ═══");
   {
-    const { opponent, escrowPda, treasury } = await setupFullEscrow(connection, authority, "t3");
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t3");
 
     const oppBalBefore = await connection.getBalance(opponent.publicKey);
     const tx = new Transaction().add(ixForfeit(
@@ -335,12 +847,13 @@ async function main() {
   // ═══════════════════════════════════════════
   console.log("\n═══ Test 4: Forfeit (opponent forfeits) ═══");
   {
-    const { opponent, escrowPda, treasury } = await setupFullEscrow(connection, authority, "t4");
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t4");
 
     const hostBalBefore = await connection.getBalance(authority.publicKey);
     const tx = new Transaction().add(ixForfeit(
       authority.publicKey, escrowPda,
-      authority.publicKey, treasury, opponent.publicKey // forfeiter = opponent
+      authority.publicKey, treasury, opponent.publicKey, // forfeiter = opponent
+      FORFEIT_SURRENDER
     ));
     await expectSuccess(connection, tx, [authority], "Forfeit by opponent");
 
@@ -358,15 +871,13 @@ async function main() {
     const opponent = Keypair.generate();
     const lobbyIdHash = hashLobby(`t5-${Date.now()}`);
     const [escrowPda] = deriveEscrowPda(lobbyIdHash);
-    const treasury = authority.publicKey;
 
     // Initialize
     await sendAndConfirmTransaction(connection,
-      new Transaction().add(ixInitialize(
-        authority.publicKey, escrowPda, lobbyIdHash,
-        authority.publicKey, opponent.publicKey, WAGER_LAMPORTS,
-        PublicKey.default, treasury
-      )), [authority]);
+      new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
 
     // Host deposits SOL normally
     await sendAndConfirmTransaction(connection,
@@ -396,11 +907,10 @@ async function main() {
     const [escrowPda] = deriveEscrowPda(lobbyIdHash);
 
     await sendAndConfirmTransaction(connection,
-      new Transaction().add(ixInitialize(
-        authority.publicKey, escrowPda, lobbyIdHash,
-        authority.publicKey, opponent.publicKey, WAGER_LAMPORTS,
-        PublicKey.default, authority.publicKey
-      )), [authority]);
+      new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
 
     // First deposit succeeds
     await sendAndConfirmTransaction(connection,
@@ -417,7 +927,7 @@ async function main() {
   // ═══════════════════════════════════════════
   console.log("\n═══ Test 7: Error — unauthorized settle ═══");
   {
-    const { opponent, escrowPda, treasury } = await setupFullEscrow(connection, authority, "t7");
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t7");
 
     // Opponent tries to settle (not the authority)
     const badTx = new Transaction().add(ixSettle(
@@ -438,11 +948,10 @@ async function main() {
     const [escrowPda] = deriveEscrowPda(lobbyIdHash);
 
     await sendAndConfirmTransaction(connection,
-      new Transaction().add(ixInitialize(
-        authority.publicKey, escrowPda, lobbyIdHash,
-        authority.publicKey, opponent.publicKey, WAGER_LAMPORTS,
-        PublicKey.default, authority.publicKey
-      )), [authority]);
+      new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
 
     // Only host deposits
     await sendAndConfirmTransaction(connection,
@@ -451,7 +960,7 @@ async function main() {
     // Try to settle with only 1 deposit
     const prematureTx = new Transaction().add(ixSettle(
       authority.publicKey, escrowPda,
-      authority.publicKey, authority.publicKey, authority.publicKey
+      authority.publicKey, treasury, authority.publicKey
     ));
     await expectFailure(connection, prematureTx, [authority],
       "Premature settle rejected", "EscrowNotFunded");
@@ -462,7 +971,7 @@ async function main() {
   // ═══════════════════════════════════════════
   console.log("\n═══ Test 9: Error — invalid winner ═══");
   {
-    const { escrowPda, treasury } = await setupFullEscrow(connection, authority, "t9");
+    const { escrowPda } = await setupFullEscrow(connection, authority, treasury, "t9");
 
     // Try to settle with a random address as winner
     const randomWinner = Keypair.generate().publicKey;
@@ -479,7 +988,7 @@ async function main() {
   // ═══════════════════════════════════════════
   console.log("\n═══ Test 10: Error — invalid forfeiter ═══");
   {
-    const { opponent, escrowPda, treasury } = await setupFullEscrow(connection, authority, "t10");
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t10");
 
     const randomForfeiter = Keypair.generate().publicKey;
     const badForfeitTx = new Transaction().add(ixForfeit(
@@ -501,11 +1010,10 @@ async function main() {
     const [escrowPda] = deriveEscrowPda(lobbyIdHash);
 
     await sendAndConfirmTransaction(connection,
-      new Transaction().add(ixInitialize(
-        authority.publicKey, escrowPda, lobbyIdHash,
-        authority.publicKey, opponent.publicKey, WAGER_LAMPORTS,
-        PublicKey.default, authority.publicKey
-      )), [authority]);
+      new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
 
     // Fund random user
     await sendAndConfirmTransaction(connection,
@@ -520,6 +1028,869 @@ async function main() {
       "Unauthorized deposit rejected", "NotAuthorized");
   }
 
+  // ═══════════════════════════════════════════
+  // Test 12: Error — unknown forfeit reason
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 12: Error — unknown forfeit reason ═══");
+  {
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t12");
+
+    const badReasonTx = new Transaction().add(ixForfeit(
+      authority.publicKey, escrowPda,
+      authority.publicKey, treasury, opponent.publicKey, 4
+    ));
+    await expectFailure(connection, badReasonTx, [authority],
+      "Unknown forfeit reason rejected", "InvalidForfeitReason");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 13: Error — player as treasury
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 13: Error — player as treasury ═══");
+  {
+    const opponent = Keypair.generate();
+    const lobbyIdHash = hashLobby(`t13-${Date.now()}`);
+    const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+
+    const hostTreasuryTx = new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+      lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+      tokenMint: PublicKey.default, treasury: authority.publicKey,
+    }));
+    await expectFailure(connection, hostTreasuryTx, [authority],
+      "Host as treasury rejected", "InvalidTreasury");
+
+    const overrideTx = new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+      lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+      tokenMint: PublicKey.default, treasury: authority.publicKey, allowPlayerTreasury: true,
+    }));
+    await expectSuccess(connection, overrideTx, [authority], "Host as treasury with override");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 14: Authority rotation
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 14: Authority rotation ═══");
+  {
+    const { escrowPda } = await setupFullEscrow(connection, authority, treasury, "t14");
+    const newAuthority = Keypair.generate();
+    await fund(connection, authority, newAuthority.publicKey, 10_000_000);
+
+    await expectSuccess(connection,
+      new Transaction().add(ixTransferAuthority(
+        authority.publicKey, escrowPda, newAuthority.publicKey
+      )), [authority], "Transfer authority");
+
+    const oldSettleTx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda,
+      authority.publicKey, treasury, authority.publicKey
+    ));
+    await expectFailure(connection, oldSettleTx, [authority],
+      "Old authority can no longer settle", "NotAuthorized");
+
+    // The original authority paid the rent and still gets it back
+    const newSettleTx = new Transaction().add(ixSettle(
+      newAuthority.publicKey, escrowPda,
+      authority.publicKey, treasury, authority.publicKey, false, authority.publicKey
+    ));
+    await expectSuccess(connection, newSettleTx, [newAuthority], "New authority settles");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 15: Cancel before and after a deposit
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 15: Cancel before and after a deposit ═══");
+  {
+    for (const deposited of [false, true]) {
+      const opponent = Keypair.generate();
+      const lobbyIdHash = hashLobby(`t15-${deposited}-${Date.now()}`);
+      const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+
+      await sendAndConfirmTransaction(connection,
+        new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+          lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+          tokenMint: PublicKey.default, treasury,
+        })), [authority]);
+      if (deposited) {
+        await sendAndConfirmTransaction(connection,
+          new Transaction().add(ixDeposit(authority.publicKey, escrowPda)), [authority]);
+      }
+
+      const cancelTx = new Transaction().add(ixCancelEscrow(authority.publicKey, escrowPda));
+      if (deposited) {
+        await expectFailure(connection, cancelTx, [authority],
+          "Cancel after a single deposit rejected", "CannotCancelFunded");
+      } else {
+        await expectSuccess(connection, cancelTx, [authority], "Cancel unfunded escrow");
+        const closed = await connection.getAccountInfo(escrowPda);
+        if (closed === null) {
+          console.log("  ✓ PDA closed after cancel");
+          passed++;
+        } else {
+          console.log("  ✗ PDA should be closed");
+          failed++;
+        }
+      }
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 16: Update wager before and after funding
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 16: Update wager before and after funding ═══");
+  {
+    const opponent = Keypair.generate();
+    const lobbyIdHash = hashLobby(`t16-${Date.now()}`);
+    const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+    const newWager = WAGER_LAMPORTS * 2;
+
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
+
+    await expectSuccess(connection,
+      new Transaction().add(ixUpdateWager(authority.publicKey, escrowPda, newWager, newWager)),
+      [authority], "Update wager before any deposit");
+
+    const escrowBalBefore = await connection.getBalance(escrowPda);
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixDeposit(authority.publicKey, escrowPda)), [authority]);
+    const deposited = (await connection.getBalance(escrowPda)) - escrowBalBefore;
+    if (deposited === newWager) {
+      console.log(`  ✓ Deposit took the updated wager (${deposited} lamports)`);
+      passed++;
+    } else {
+      console.log(`  ✗ Deposit took ${deposited} lamports (expected ${newWager})`);
+      failed++;
+    }
+
+    const fundedTx = new Transaction().add(
+      ixUpdateWager(authority.publicKey, escrowPda, WAGER_LAMPORTS, WAGER_LAMPORTS)
+    );
+    await expectFailure(connection, fundedTx, [authority],
+      "Update wager after a deposit rejected", "CannotModifyFunded");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 17: Error — fee diverted to a wrong treasury
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 17: Error — fee diverted to a wrong treasury ═══");
+  {
+    const { escrowPda } = await setupFullEscrow(connection, authority, treasury, "t17");
+
+    const wrongTreasury = Keypair.generate().publicKey;
+    const divertTx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda,
+      authority.publicKey, wrongTreasury, authority.publicKey
+    ));
+    await expectFailure(connection, divertTx, [authority],
+      "Settle to a wrong treasury rejected", "NotAuthorized");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 18: Error — winner is the treasury
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 18: Error — winner is the treasury ═══");
+  {
+    // The override lets the host double as treasury, so a host win would
+    // name one account in both roles
+    const { escrowPda } = await setupFullEscrow(
+      connection, authority, authority.publicKey, "t18", { allowPlayerTreasury: true }
+    );
+
+    const aliasTx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda,
+      authority.publicKey, authority.publicKey, authority.publicKey
+    ));
+    await expectFailure(connection, aliasTx, [authority],
+      "Winner as treasury rejected", "WinnerIsTreasury");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 19: Reclaimed rent to rent payer or winner
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 19: Reclaimed rent to rent payer or winner ═══");
+  {
+    for (const rentToWinner of [false, true]) {
+      const opponent = Keypair.generate();
+      const rentPayer = Keypair.generate();
+      const lobbyIdHash = hashLobby(`t19-${rentToWinner}-${Date.now()}`);
+      const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+      await fund(connection, authority, opponent.publicKey, WAGER_LAMPORTS + 10_000_000);
+      await fund(connection, authority, rentPayer.publicKey, 10_000_000);
+
+      // A separate rent payer, so its balance moves only by the rent
+      await sendAndConfirmTransaction(connection,
+        new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+          lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+          tokenMint: PublicKey.default, treasury,
+        }, rentPayer.publicKey)), [authority, rentPayer]);
+      await sendAndConfirmTransaction(connection,
+        new Transaction().add(ixDeposit(authority.publicKey, escrowPda)), [authority]);
+      await sendAndConfirmTransaction(connection,
+        new Transaction().add(ixDeposit(opponent.publicKey, escrowPda)), [opponent]);
+
+      const rent = (await connection.getBalance(escrowPda)) - WAGER_LAMPORTS * 2;
+      const payout = WAGER_LAMPORTS * 2 * 0.9;
+      const oppBalBefore = await connection.getBalance(opponent.publicKey);
+      const payerBalBefore = await connection.getBalance(rentPayer.publicKey);
+
+      const tx = new Transaction().add(ixSettle(
+        authority.publicKey, escrowPda,
+        opponent.publicKey, treasury, opponent.publicKey, rentToWinner, rentPayer.publicKey
+      ));
+      await expectSuccess(connection, tx, [authority],
+        `Settle with rent to ${rentToWinner ? "winner" : "rent payer"}`);
+
+      const oppGain = (await connection.getBalance(opponent.publicKey)) - oppBalBefore;
+      const payerGain = (await connection.getBalance(rentPayer.publicKey)) - payerBalBefore;
+      const expected = rentToWinner ? [payout + rent, 0] : [payout, rent];
+      if (oppGain === expected[0] && payerGain === expected[1]) {
+        console.log(`  ✓ Winner +${oppGain}, rent payer +${payerGain} lamports`);
+        passed++;
+      } else {
+        console.log(`  ✗ Winner +${oppGain}, rent payer +${payerGain} lamports ` +
+          `(expected +${expected[0]}, +${expected[1]})`);
+        failed++;
+      }
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 20: Separate settle authority
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 20: Separate settle authority ═══");
+  {
+    const settler = Keypair.generate();
+    await fund(connection, authority, settler.publicKey, 10_000_000);
+    const { escrowPda } = await setupFullEscrow(
+      connection, authority, treasury, "t20", { settleAuthority: settler.publicKey }
+    );
+
+    const adminSettleTx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda,
+      authority.publicKey, treasury, authority.publicKey
+    ));
+    await expectFailure(connection, adminSettleTx, [authority],
+      "Authority without settle rights rejected", "NotAuthorized");
+
+    const redirectTx = new Transaction().add(ixSettle(
+      settler.publicKey, escrowPda,
+      authority.publicKey, treasury, authority.publicKey, false, settler.publicKey
+    ));
+    await expectFailure(connection, redirectTx, [settler],
+      "Settle authority can't redirect rent", "NotAuthorized");
+
+    const settleTx = new Transaction().add(ixSettle(
+      settler.publicKey, escrowPda,
+      authority.publicKey, treasury, authority.publicKey, false, authority.publicKey
+    ));
+    await expectSuccess(connection, settleTx, [settler], "Settle authority settles");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 21: Tiebreak pays the first depositor
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 21: Tiebreak pays the first depositor ═══");
+  {
+    const opponent = Keypair.generate();
+    const lobbyIdHash = hashLobby(`t21-${Date.now()}`);
+    const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+    await fund(connection, authority, opponent.publicKey, WAGER_LAMPORTS + 10_000_000);
+
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
+
+    // The opponent deposits first, so the host's seat doesn't decide it
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixDeposit(opponent.publicKey, escrowPda)), [opponent]);
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixDeposit(authority.publicKey, escrowPda)), [authority]);
+
+    const hostTx = new Transaction().add(ixSettleTiebreak(
+      authority.publicKey, escrowPda, authority.publicKey, treasury
+    ));
+    await expectFailure(connection, hostTx, [authority],
+      "Tiebreak to the second depositor rejected", "InvalidWinner");
+
+    const oppBalBefore = await connection.getBalance(opponent.publicKey);
+    const tx = new Transaction().add(ixSettleTiebreak(
+      authority.publicKey, escrowPda, opponent.publicKey, treasury
+    ));
+    await expectSuccess(connection, tx, [authority], "Tiebreak to the first depositor");
+
+    const payout = (await connection.getBalance(opponent.publicKey)) - oppBalBefore;
+    const expectedPayout = WAGER_LAMPORTS * 2 * 0.9;
+    if (payout === expectedPayout) {
+      console.log(`  ✓ First depositor received ${payout} lamports`);
+      passed++;
+    } else {
+      console.log(`  ✗ First depositor received ${payout} lamports (expected ${expectedPayout})`);
+      failed++;
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 22: Dispute, then mutual cancel
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 22: Dispute, then mutual cancel ═══");
+  {
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t22");
+    const cancelIx = () => ixMutualCancel(
+      escrowPda, authority.publicKey, opponent.publicKey, authority.publicKey
+    );
+
+    await expectFailure(connection, new Transaction().add(cancelIx()),
+      [authority, opponent], "Mutual cancel without a dispute rejected", "NotDisputed");
+
+    await expectSuccess(connection,
+      new Transaction().add(ixRaiseDispute(opponent.publicKey, escrowPda)),
+      [authority, opponent], "Opponent raises a dispute");
+
+    const frozenTx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda,
+      authority.publicKey, treasury, authority.publicKey
+    ));
+    await expectFailure(connection, frozenTx, [authority],
+      "Settle while disputed rejected", "Disputed");
+
+    const oppBalBefore = await connection.getBalance(opponent.publicKey);
+    await expectSuccess(connection, new Transaction().add(cancelIx()),
+      [authority, opponent], "Both players cancel the disputed escrow");
+
+    // The authority pays the fee, so the opponent's stake comes back whole
+    const refund = (await connection.getBalance(opponent.publicKey)) - oppBalBefore;
+    if (refund === WAGER_LAMPORTS) {
+      console.log(`  ✓ Opponent refunded ${refund} lamports`);
+      passed++;
+    } else {
+      console.log(`  ✗ Opponent refunded ${refund} lamports (expected ${WAGER_LAMPORTS})`);
+      failed++;
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 23: Double forfeit refunds each stake less the fee
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 23: Double forfeit ═══");
+  {
+    const abandonmentFeeBps = 500;
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t23");
+
+    const oppBalBefore = await connection.getBalance(opponent.publicKey);
+    const treasuryBalBefore = await connection.getBalance(treasury);
+    const tx = new Transaction().add(ixSettleDoubleForfeit(
+      authority.publicKey, escrowPda, authority.publicKey, authority.publicKey, opponent.publicKey,
+      treasury, abandonmentFeeBps
+    ));
+    await expectSuccess(connection, tx, [authority], "Double forfeit");
+
+    const refund = (await connection.getBalance(opponent.publicKey)) - oppBalBefore;
+    const fee = (await connection.getBalance(treasury)) - treasuryBalBefore;
+    const expectedRefund = WAGER_LAMPORTS * (10_000 - abandonmentFeeBps) / 10_000;
+    if (refund === expectedRefund && fee === (WAGER_LAMPORTS - expectedRefund) * 2) {
+      console.log(`  ✓ Opponent refunded ${refund} lamports, treasury took ${fee}`);
+      passed++;
+    } else {
+      console.log(`  ✗ Opponent refunded ${refund}, treasury took ${fee} ` +
+        `(expected ${expectedRefund}, ${(WAGER_LAMPORTS - expectedRefund) * 2})`);
+      failed++;
+    }
+
+    // Both players must have deposited
+    const halfOpponent = Keypair.generate();
+    const lobbyIdHash = hashLobby(`t23-half-${Date.now()}`);
+    const [halfPda] = deriveEscrowPda(lobbyIdHash);
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixInitialize(authority.publicKey, halfPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: halfOpponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixDeposit(authority.publicKey, halfPda)), [authority]);
+
+    const halfTx = new Transaction().add(ixSettleDoubleForfeit(
+      authority.publicKey, halfPda, authority.publicKey, authority.publicKey, halfOpponent.publicKey,
+      treasury, abandonmentFeeBps
+    ));
+    await expectFailure(connection, halfTx, [authority],
+      "Double forfeit of a half-funded escrow rejected", "EscrowNotFunded");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 24: Error — expire refund before expiry
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 24: Error — expire refund before expiry ═══");
+  {
+    // The far side of the boundary is covered by the `expired` unit test;
+    // devnet can't wait out the escrow lifetime
+    const { opponent, escrowPda } = await setupFullEscrow(connection, authority, treasury, "t24");
+    const stranger = Keypair.generate();
+    await fund(connection, authority, stranger.publicKey, 10_000_000);
+
+    const earlyTx = new Transaction().add(ixExpireRefund(
+      stranger.publicKey, escrowPda, authority.publicKey, opponent.publicKey, authority.publicKey
+    ));
+    await expectFailure(connection, earlyTx, [stranger],
+      "Expire refund of a live escrow rejected", "NotYetExpired");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 25: Rent payer one lamport short
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 25: Rent payer one lamport short ═══");
+  {
+    // Size the escrow rent from a live escrow, then cancel it
+    const sizedHash = hashLobby(`t25-sized-${Date.now()}`);
+    const [sizedPda] = deriveEscrowPda(sizedHash);
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixInitialize(authority.publicKey, sizedPda, {
+        lobbyIdHash: sizedHash, host: authority.publicKey,
+        opponent: Keypair.generate().publicKey, tokenMint: PublicKey.default, treasury,
+      })), [authority]);
+    const escrowSize = (await connection.getAccountInfo(sizedPda))!.data.length;
+    const rent = await connection.getMinimumBalanceForRentExemption(escrowSize);
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixCancelEscrow(authority.publicKey, sizedPda)), [authority]);
+
+    for (const shortfall of [1, 0]) {
+      const opponent = Keypair.generate();
+      const payer = Keypair.generate();
+      const lobbyIdHash = hashLobby(`t25-${shortfall}-${Date.now()}`);
+      const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+      await fund(connection, authority, payer.publicKey, rent - shortfall);
+
+      // The authority pays the transaction fee, so the payer's whole
+      // balance is available for rent
+      const tx = new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      }, payer.publicKey));
+      if (shortfall > 0) {
+        await expectFailure(connection, tx, [authority, payer],
+          "Payer one lamport short rejected", "InsufficientRent");
+      } else {
+        await expectSuccess(connection, tx, [authority, payer], "Payer with exact rent");
+      }
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 26: Losing a settle race vs a missing escrow
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 26: Losing a settle race vs a missing escrow ═══");
+  {
+    const { escrowPda } = await setupFullEscrow(connection, authority, treasury, "t26");
+    const settleIx = (pda: PublicKey) => ixSettle(
+      authority.publicKey, pda, authority.publicKey, treasury, authority.publicKey
+    );
+    await sendAndConfirmTransaction(connection, new Transaction().add(settleIx(escrowPda)), [authority]);
+
+    const [missingPda] = deriveEscrowPda(hashLobby(`t26-missing-${Date.now()}`));
+    const cases: [PublicKey, string, string][] = [
+      [escrowPda, "already-settled", "Second settle classified as already settled"],
+      [missingPda, "never-existed", "Settle of a missing escrow classified as never existed"],
+    ];
+    for (const [pda, expected, label] of cases) {
+      try {
+        await sendAndConfirmTransaction(connection, new Transaction().add(settleIx(pda)), [authority]);
+        console.log(`  ✗ ${label} — SHOULD HAVE FAILED but succeeded`);
+        failed++;
+      } catch (err: any) {
+        const kind = await classifySettleFailure(connection, pda, err);
+        if (kind === expected) {
+          console.log(`  ✓ ${label}`);
+          passed++;
+        } else {
+          console.log(`  ✗ ${label} — got ${kind}`);
+          failed++;
+        }
+      }
+    }
+  }
+
+  // SPL fixtures for the tests below
+  const mint = await createMint(connection, authority);
+
+  // ═══════════════════════════════════════════
+  // Test 27: Error — SPL payout to someone else's token account
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 27: Error — SPL payout to someone else's token account ═══");
+  {
+    const { opponent, escrowPda, hostTa, opponentTa, treasuryTa, escrowTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t27");
+    const spl = (winnerTa: PublicKey) => ({ winnerTa, treasuryTa, escrowTa, mint });
+
+    // Opponent wins, but the payout account is the host's
+    const wrongOwnerTx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda, opponent.publicKey, treasury, opponent.publicKey,
+      false, authority.publicKey, spl(hostTa)
+    ));
+    await expectFailure(connection, wrongOwnerTx, [authority],
+      "Payout to a token account the winner doesn't own rejected", "WrongTokenOwner");
+
+    const tx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda, opponent.publicKey, treasury, opponent.publicKey,
+      false, authority.publicKey, spl(opponentTa)
+    ));
+    await expectSuccess(connection, tx, [authority], "SPL settle to the winner's token account");
+
+    const balance = await tokenBalance(connection, opponentTa);
+    const expectedPayout = WAGER_TOKENS * 2 * 0.9;
+    if (balance === expectedPayout) {
+      console.log(`  ✓ Winner's token account holds ${balance}`);
+      passed++;
+    } else {
+      console.log(`  ✗ Winner's token account holds ${balance} (expected ${expectedPayout})`);
+      failed++;
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 28: Error — SPL deposit into a non-escrow token account
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 28: Error — SPL deposit into a non-escrow token account ═══");
+  {
+    const { opponent, escrowPda, opponentTa, treasuryTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t28", false);
+
+    // Right mint, wrong owner: the treasury's account instead of the escrow ATA
+    const tx = new Transaction().add(ixDeposit(opponent.publicKey, escrowPda, {
+      source: opponentTa, escrowTa: treasuryTa, mint,
+    }));
+    await expectFailure(connection, tx, [authority, opponent],
+      "Deposit into the treasury's token account rejected", "InvalidEscrowTokenAccount");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 29: Error — SPL deposit from someone else's token account
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 29: Error — SPL deposit from someone else's token account ═══");
+  {
+    const { opponent, escrowPda, hostTa, escrowTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t29", false);
+
+    // The opponent signs, but the tokens would come out of the host's account
+    const tx = new Transaction().add(ixDeposit(opponent.publicKey, escrowPda, {
+      source: hostTa, escrowTa, mint,
+    }));
+    await expectFailure(connection, tx, [authority, opponent],
+      "Deposit from a token account the depositor doesn't own rejected", "WrongTokenOwner");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 30: SPL deposit in installments, overpayment rejected
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 30: SPL deposit in installments, overpayment rejected ═══");
+  {
+    const { opponent, escrowPda, opponentTa, treasuryTa, escrowTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t30", false);
+    const accounts = { source: opponentTa, escrowTa, mint };
+    const firstPart = WAGER_TOKENS * 0.4;
+
+    const firstTx = new Transaction().add(
+      ixDepositInstallment(opponent.publicKey, escrowPda, firstPart, accounts)
+    );
+    await expectSuccess(connection, firstTx, [authority, opponent], "First installment (40%)");
+
+    // Part-paid seat: the escrow isn't funded yet
+    const earlyTx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda, opponent.publicKey, treasury, opponent.publicKey,
+      false, authority.publicKey,
+      { winnerTa: opponentTa, treasuryTa, escrowTa, mint }
+    ));
+    await expectFailure(connection, earlyTx, [authority],
+      "Settle with a part-paid seat rejected", "EscrowNotFunded");
+
+    const overTx = new Transaction().add(
+      ixDepositInstallment(opponent.publicKey, escrowPda, WAGER_TOKENS - firstPart + 1, accounts)
+    );
+    await expectFailure(connection, overTx, [authority, opponent],
+      "Installment past the wager rejected", "ExceedsWager");
+
+    const lastTx = new Transaction().add(
+      ixDepositInstallment(opponent.publicKey, escrowPda, WAGER_TOKENS - firstPart, accounts)
+    );
+    await expectSuccess(connection, lastTx, [authority, opponent], "Final installment (60%)");
+
+    const held = await tokenBalance(connection, escrowTa);
+    if (held === WAGER_TOKENS * 2) {
+      console.log(`  ✓ Escrow holds both full wagers (${held})`);
+      passed++;
+    } else {
+      console.log(`  ✗ Escrow holds ${held} (expected ${WAGER_TOKENS * 2})`);
+      failed++;
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 31: Reclaim a single SPL deposit after the deposit deadline
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 31: Reclaim a single SPL deposit after the deposit deadline ═══");
+  {
+    const depositDeadline = nowSecs() + 30;
+    const { escrowPda, hostTa, escrowTa } = await setupSplEscrow(
+      connection, authority, treasury, mint, "t31", false, { depositDeadline }
+    );
+    const reclaim = () => new Transaction().add(ixReclaimUnfunded(
+      authority.publicKey, escrowPda, authority.publicKey, authority.publicKey,
+      { depositorTa: hostTa, escrowTa, mint }
+    ));
+
+    await expectFailure(connection, reclaim(), [authority],
+      "Reclaim while the deposit window is open rejected", "DepositWindowOpen");
+
+    // Wait out the window, with slack for cluster clock drift
+    await sleep((depositDeadline - nowSecs() + 10) * 1000);
+    const hostBefore = await tokenBalance(connection, hostTa);
+    await expectSuccess(connection, reclaim(), [authority], "Reclaim the host's lone deposit");
+
+    const refunded = (await tokenBalance(connection, hostTa)) - hostBefore;
+    const [escrowInfo, ataInfo] = await Promise.all([
+      connection.getAccountInfo(escrowPda),
+      connection.getAccountInfo(escrowTa),
+    ]);
+    if (refunded === WAGER_TOKENS && escrowInfo === null && ataInfo === null) {
+      console.log(`  ✓ Host refunded ${refunded}; escrow and its token account closed`);
+      passed++;
+    } else {
+      console.log(`  ✗ Refund ${refunded} (expected ${WAGER_TOKENS}), escrow closed: ` +
+        `${escrowInfo === null}, token account closed: ${ataInfo === null}`);
+      failed++;
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 32: SPL double forfeit
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 32: SPL double forfeit ═══");
+  {
+    const abandonmentFeeBps = 500;
+    const { opponent, escrowPda, hostTa, opponentTa, treasuryTa, escrowTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t32");
+    const doubleForfeit = (feeTa: PublicKey) => new Transaction().add(ixSettleDoubleForfeit(
+      authority.publicKey, escrowPda, authority.publicKey, authority.publicKey, opponent.publicKey,
+      treasury, abandonmentFeeBps,
+      { hostTa, opponentTa, treasuryTa: feeTa, escrowTa, mint }
+    ));
+
+    // The fee must land with the treasury, not a player
+    await expectFailure(connection, doubleForfeit(hostTa), [authority],
+      "Abandonment fee to a player's token account rejected", "NotAuthorized");
+
+    const oppBefore = await tokenBalance(connection, opponentTa);
+    const treasuryBefore = await tokenBalance(connection, treasuryTa);
+    await expectSuccess(connection, doubleForfeit(treasuryTa), [authority], "SPL double forfeit");
+
+    const refund = (await tokenBalance(connection, opponentTa)) - oppBefore;
+    const fee = (await tokenBalance(connection, treasuryTa)) - treasuryBefore;
+    const expectedRefund = WAGER_TOKENS * (10_000 - abandonmentFeeBps) / 10_000;
+    if (refund === expectedRefund && fee === (WAGER_TOKENS - expectedRefund) * 2) {
+      console.log(`  ✓ Opponent refunded ${refund}, treasury took ${fee}`);
+      passed++;
+    } else {
+      console.log(`  ✗ Opponent refunded ${refund}, treasury took ${fee} ` +
+        `(expected ${expectedRefund}, ${(WAGER_TOKENS - expectedRefund) * 2})`);
+      failed++;
+    }
+
+    // The emptied escrow token account goes back to the rent payer too
+    if ((await connection.getAccountInfo(escrowTa)) === null) {
+      console.log("  ✓ Escrow token account closed");
+      passed++;
+    } else {
+      console.log("  ✗ Escrow token account still open");
+      failed++;
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 33: SPL settle closes the escrow's token account
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 33: SPL settle closes the escrow's token account ═══");
+  {
+    const { escrowPda, hostTa, treasuryTa, escrowTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t33");
+
+    const tx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda, authority.publicKey, treasury, authority.publicKey,
+      false, authority.publicKey, { winnerTa: hostTa, treasuryTa, escrowTa, mint }
+    ));
+    await expectSuccess(connection, tx, [authority], "SPL settle to the host");
+
+    const ataInfo = await connection.getAccountInfo(escrowTa);
+    if (ataInfo === null) {
+      console.log("  ✓ Escrow token account closed, rent returned to the rent payer");
+      passed++;
+    } else {
+      console.log(`  ✗ Escrow token account still open (${ataInfo.lamports} lamports)`);
+      failed++;
+    }
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 34: Error — SPL fee diverted to a player's token account
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 34: Error — SPL fee diverted to a player's token account ═══");
+  {
+    const { escrowPda, hostTa, opponentTa, escrowTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t34");
+
+    // Real treasury wallet, but the fee would go to the opponent's tokens
+    const tx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda, authority.publicKey, treasury, authority.publicKey,
+      false, authority.publicKey, { winnerTa: hostTa, treasuryTa: opponentTa, escrowTa, mint }
+    ));
+    await expectFailure(connection, tx, [authority],
+      "Fee to a token account the treasury doesn't own rejected", "NotAuthorized");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 35: Error — deposit_both after an installment
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 35: Error — deposit_both after an installment ═══");
+  {
+    const opponent = Keypair.generate();
+    const lobbyIdHash = hashLobby(`t35-${Date.now()}`);
+    const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+    await fund(connection, authority, opponent.publicKey, WAGER_LAMPORTS + 10_000_000);
+    await sendAndConfirmTransaction(connection,
+      new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury,
+      })), [authority]);
+
+    // The host's seat is part-paid, so neither deposited flag is set yet
+    await sendAndConfirmTransaction(connection, new Transaction().add(
+      ixDepositInstallment(authority.publicKey, escrowPda, WAGER_LAMPORTS / 2)
+    ), [authority]);
+
+    const tx = new Transaction().add(
+      ixDepositBoth(authority.publicKey, opponent.publicKey, escrowPda)
+    );
+    await expectFailure(connection, tx, [authority, opponent],
+      "deposit_both over a part-paid seat rejected", "AlreadyDeposited");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 36: Error — mutual cancel on a sponsored escrow
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 36: Error — mutual cancel on a sponsored escrow ═══");
+  {
+    const opponent = Keypair.generate();
+    const lobbyIdHash = hashLobby(`t36-${Date.now()}`);
+    const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+    await fund(connection, authority, opponent.publicKey, 10_000_000);
+    await sendAndConfirmTransaction(connection, new Transaction().add(
+      ixInitialize(authority.publicKey, escrowPda, {
+        lobbyIdHash, host: authority.publicKey, opponent: opponent.publicKey,
+        tokenMint: PublicKey.default, treasury, sponsored: true,
+      }),
+      ixSponsorDeposit(authority.publicKey, escrowPda),
+    ), [authority]);
+
+    await expectSuccess(connection,
+      new Transaction().add(ixRaiseDispute(opponent.publicKey, escrowPda)),
+      [opponent], "Opponent disputes the sponsored match");
+
+    // Refunding would hand the authority's stake to the players
+    const tx = new Transaction().add(ixMutualCancel(
+      escrowPda, authority.publicKey, opponent.publicKey, authority.publicKey
+    ));
+    await expectFailure(connection, tx, [authority, opponent],
+      "Mutual cancel of a sponsored escrow rejected", "SponsoredEscrow");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 37: Join as the treasury of a house match
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 37: Join as the treasury of a house match ═══");
+  {
+    const house = Keypair.generate();
+    await fund(connection, authority, house.publicKey, 2 * WAGER_LAMPORTS + 20_000_000);
+
+    const openSeat = async (testName: string, allowPlayerTreasury: boolean) => {
+      const lobbyIdHash = hashLobby(`${testName}-${Date.now()}`);
+      const [escrowPda] = deriveEscrowPda(lobbyIdHash);
+      await sendAndConfirmTransaction(connection,
+        new Transaction().add(ixInitialize(authority.publicKey, escrowPda, {
+          lobbyIdHash, host: authority.publicKey, opponent: PublicKey.default,
+          tokenMint: PublicKey.default, treasury: house.publicKey, allowPlayerTreasury,
+        })), [authority]);
+      return escrowPda;
+    };
+
+    const strict = await openSeat("t37a", false);
+    await expectFailure(connection, new Transaction().add(ixJoin(house.publicKey, strict)),
+      [house], "Treasury joining without the override rejected", "InvalidTreasury");
+
+    const houseMatch = await openSeat("t37b", true);
+    await expectSuccess(connection, new Transaction().add(ixJoin(house.publicKey, houseMatch)),
+      [house], "Treasury joins a match created with allow_player_treasury");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 38: Error — SPL settle through a stand-in token program
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 38: Error — SPL settle through a stand-in token program ═══");
+  {
+    const { opponent, escrowPda, opponentTa, treasuryTa, escrowTa } =
+      await setupSplEscrow(connection, authority, treasury, mint, "t38");
+    const spl = { winnerTa: opponentTa, treasuryTa, escrowTa, mint };
+
+    // A program that could call back into the escrow mid-payout never gets
+    // the CPI: only the SPL Token and Token-2022 programs are accepted
+    const keys = settleKeys(
+      authority.publicKey, escrowPda, authority.publicKey, opponent.publicKey, treasury, spl
+    ).map((k) => k.pubkey.equals(TOKEN_PROGRAM_ID)
+      ? { ...k, pubkey: ASSOCIATED_TOKEN_PROGRAM_ID }
+      : k);
+    const hijackTx = new Transaction().add(new TransactionInstruction({
+      keys,
+      programId: PROGRAM_ID,
+      data: Buffer.concat([disc("settle"), pubkeyBuf(opponent.publicKey), boolBuf(false)]),
+    }));
+    await expectFailure(connection, hijackTx, [authority],
+      "Settle with a non-token program rejected", "InvalidProgramId");
+
+    // Nothing was left mid-settlement, so the real payout still goes through
+    const tx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda, opponent.publicKey, treasury, opponent.publicKey,
+      false, authority.publicKey, spl
+    ));
+    await expectSuccess(connection, tx, [authority], "Settle with the token program afterwards");
+  }
+
+  // ═══════════════════════════════════════════
+  // Test 39: Zero-fee settle pays the whole pot
+  // ═══════════════════════════════════════════
+  console.log("\n═══ Test 39: Zero-fee settle pays the whole pot ═══");
+  {
+    const { opponent, escrowPda } =
+      await setupFullEscrow(connection, authority, treasury, "t39", { feeBps: 0 });
+
+    const oppBalBefore = await connection.getBalance(opponent.publicKey);
+    const treasuryBalBefore = await connection.getBalance(treasury);
+    const tx = new Transaction().add(ixSettle(
+      authority.publicKey, escrowPda, opponent.publicKey, treasury, opponent.publicKey
+    ));
+    await expectSuccess(connection, tx, [authority], "Settle a zero-fee escrow");
+
+    const payout = (await connection.getBalance(opponent.publicKey)) - oppBalBefore;
+    const treasuryDelta = (await connection.getBalance(treasury)) - treasuryBalBefore;
+    if (payout === WAGER_LAMPORTS * 2 && treasuryDelta === 0) {
+      console.log(`  ✓ Winner received the full ${payout} lamports; treasury untouched`);
+      passed++;
+    } else {
+      console.log(`  ✗ Winner received ${payout} lamports, treasury moved ${treasuryDelta} (expected ${WAGER_LAMPORTS * 2} and 0)`);
+      failed++;
+    }
+  }
+
   // ═══════════════════════════════════════════
   // Results
   // ═══════════════════════════════════════════